@@ -0,0 +1,71 @@
+use std::env;
+use std::process::ExitCode;
+
+use gmtk2024::puzzle::{BarPuzzle, PuzzleBar};
+
+const USAGE: &str = "\
+Usage: bar_solver [--budget N] BAR...
+
+Each BAR is start,minimum,maximum,target for one BarController, where target
+is a value or an inclusive range like 4..6. Without --budget the MiniGame is
+treated as unlimited.
+
+Example: bar_solver --budget 5 2,1,8,4 6,2,10,3..5";
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let mut puzzle = BarPuzzle::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            "--budget" => {
+                let Some(budget) = args.next().and_then(|b| b.parse().ok())
+                else {
+                    eprintln!("--budget needs a number\n\n{}", USAGE);
+                    return ExitCode::from(2);
+                };
+                puzzle.budget = Some(budget);
+            }
+            bar => match bar.parse::<PuzzleBar>() {
+                Ok(bar) => puzzle.bars.push(bar),
+                Err(err) => {
+                    eprintln!("{}\n\n{}", err, USAGE);
+                    return ExitCode::from(2);
+                }
+            },
+        }
+    }
+    if puzzle.bars.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    match puzzle.solve() {
+        Ok(solution) => {
+            match puzzle.budget {
+                Some(budget) => println!(
+                    "Solvable in {} clicks ({} to spare)",
+                    solution.clicks,
+                    budget.saturating_sub(solution.clicks)
+                ),
+                None => println!("Solvable in {} clicks", solution.clicks),
+            }
+            for step in solution.moves {
+                let sign = if step.up { '+' } else { '-' };
+                if step.snap {
+                    println!("  bar {}: {}1 to snap back", step.bar, sign);
+                } else {
+                    println!("  bar {}: {}{}", step.bar, sign, step.clicks);
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            println!("Unsolvable: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
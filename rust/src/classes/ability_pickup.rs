@@ -0,0 +1,35 @@
+use godot::classes::{Area2D, IArea2D};
+use godot::prelude::*;
+
+use super::player::{Ability, Player};
+
+/// Grants the player an [`Ability`] when they walk into it.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct AbilityPickup {
+    base: Base<Area2D>,
+    #[export]
+    ability: Ability,
+}
+
+#[godot_api]
+impl IArea2D for AbilityPickup {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            ability: Ability::DoubleJump,
+        }
+    }
+}
+
+#[godot_api]
+impl AbilityPickup {
+    #[func]
+    fn pickup(&mut self, body: Gd<Node>) {
+        let Ok(mut player) = body.try_cast::<Player>() else {
+            return;
+        };
+        player.bind_mut().grant(self.ability);
+        self.base_mut().queue_free();
+    }
+}
@@ -0,0 +1,212 @@
+use godot::classes::{AnimationPlayer, INode2D, Node2D, Time};
+use godot::prelude::*;
+
+use super::dialogue::dialogue_box::DialogueBox;
+use super::dialogue::Dialogue;
+use super::graphs::bar::BarGraph;
+use crate::puzzle::target_range;
+
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum Mood {
+    Pleased,
+    Neutral,
+    Annoyed,
+    Furious,
+}
+
+impl Mood {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mood::Pleased => "Pleased",
+            Mood::Neutral => "Neutral",
+            Mood::Annoyed => "Annoyed",
+            Mood::Furious => "Furious",
+        }
+    }
+
+    /// Variants are declared from best to worst.
+    fn is_worse_than(self, other: Mood) -> bool { self as i64 > other as i64 }
+}
+
+/// Watches the level's `BarGraph` and reacts to how far the bars are from
+/// `targets`, playing the animation named after the current [`Mood`] on an
+/// `AnimationPlayer` child and optionally opening a dialogue.
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct Boss {
+    base: Base<Node2D>,
+    /// Value the boss wants each bar at, by bar index.
+    #[export]
+    targets: PackedFloat32Array,
+    /// How far a bar may be from its target and still count as on target
+    /// when working out whether the level can be solved.
+    #[export]
+    target_tolerance: f32,
+    /// Total distance from the targets at or below which the boss is
+    /// pleased.
+    #[export]
+    pleased_within: f32,
+    #[export]
+    neutral_within: f32,
+    /// Beyond this the boss is furious.
+    #[export]
+    annoyed_within: f32,
+    #[export]
+    pleased_dialogue: Option<Gd<Dialogue>>,
+    #[export]
+    neutral_dialogue: Option<Gd<Dialogue>>,
+    #[export]
+    annoyed_dialogue: Option<Gd<Dialogue>>,
+    #[export]
+    furious_dialogue: Option<Gd<Dialogue>>,
+    /// Seconds after speaking before the boss will open another dialogue.
+    #[export]
+    speak_cooldown: f32,
+    /// Engine time in seconds the boss last spoke at.
+    last_spoke: Option<f32>,
+    #[var]
+    mood: Mood,
+    bar_graph: Option<Gd<BarGraph>>,
+    animation_player: Option<Gd<AnimationPlayer>>,
+}
+
+#[godot_api]
+impl INode2D for Boss {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            targets: PackedFloat32Array::new(),
+            target_tolerance: 0.5,
+            pleased_within: 0.5,
+            neutral_within: 2.,
+            annoyed_within: 5.,
+            pleased_dialogue: None,
+            neutral_dialogue: None,
+            annoyed_dialogue: None,
+            furious_dialogue: None,
+            speak_cooldown: 10.,
+            last_spoke: None,
+            mood: Mood::Neutral,
+            bar_graph: None,
+            animation_player: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().add_to_group("boss".into());
+        self.animation_player = self.base().try_get_node_as("AnimationPlayer");
+        let Some(mut bar_graph) = BarGraph::find(self.base().clone().upcast())
+        else {
+            godot_error!("Boss could not find a BarGraph");
+            return;
+        };
+        bar_graph.connect(
+            "bar_changed".into(),
+            Callable::from_object_method(&self.to_gd(), "bar_changed"),
+        );
+        self.bar_graph = Some(bar_graph);
+        // Bars are only created once the graph is ready, so wait a frame
+        // before the first look at them.
+        self.base_mut().call_deferred("update_mood".into(), &[]);
+    }
+}
+
+#[godot_api]
+impl Boss {
+    #[signal]
+    fn mood_changed(from: Mood, to: Mood);
+
+    #[func]
+    fn bar_changed(&mut self, _index: u32, _value: f32) { self.update_mood(); }
+
+    /// Range of values that count as on target for each bar, by bar index.
+    pub fn target_ranges(&self) -> Vec<(u32, u32)> {
+        self.targets
+            .as_slice()
+            .iter()
+            .map(|target| target_range(*target, self.target_tolerance))
+            .collect()
+    }
+
+    /// Sum of how far each targeted bar is from its target.
+    #[func]
+    fn distance(&self) -> f32 {
+        let Some(ref bar_graph) = self.bar_graph else {
+            return 0.;
+        };
+        let bar_graph = bar_graph.bind();
+        self.targets
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, target)| {
+                let value = bar_graph.bar_value(idx as u32)?;
+                Some((value - target).abs())
+            })
+            .sum()
+    }
+
+    #[func]
+    fn update_mood(&mut self) {
+        let mood = self.mood_for(self.distance());
+        if mood == self.mood {
+            return;
+        }
+        let from = self.mood;
+        self.mood = mood;
+        if let Some(ref mut animation_player) = self.animation_player {
+            if animation_player.has_animation(mood.name().into()) {
+                animation_player.play_ex().name(mood.name().into()).done();
+            }
+        }
+        // Only speak up when things get worse or are finally right, so
+        // nudging a bar back and forth across a threshold doesn't keep
+        // stopping the player.
+        if mood.is_worse_than(from) || mood == Mood::Pleased {
+            self.speak(mood);
+        }
+        self.base_mut().emit_signal(
+            "mood_changed".into(),
+            &[from.to_variant(), mood.to_variant()],
+        );
+    }
+
+    fn mood_for(&self, distance: f32) -> Mood {
+        if distance <= self.pleased_within {
+            Mood::Pleased
+        } else if distance <= self.neutral_within {
+            Mood::Neutral
+        } else if distance <= self.annoyed_within {
+            Mood::Annoyed
+        } else {
+            Mood::Furious
+        }
+    }
+
+    fn speak(&mut self, mood: Mood) {
+        let dialogue = match mood {
+            Mood::Pleased => self.pleased_dialogue.clone(),
+            Mood::Neutral => self.neutral_dialogue.clone(),
+            Mood::Annoyed => self.annoyed_dialogue.clone(),
+            Mood::Furious => self.furious_dialogue.clone(),
+        };
+        let Some(dialogue) = dialogue else {
+            return;
+        };
+        let Some(mut dialogue_box) =
+            DialogueBox::find_or_create(self.base().clone().upcast())
+        else {
+            return;
+        };
+        let now = Time::singleton().get_ticks_msec() as f32 / 1000.;
+        let cooling_down = self
+            .last_spoke
+            .is_some_and(|last_spoke| now - last_spoke < self.speak_cooldown);
+        if cooling_down || dialogue_box.bind().is_open() {
+            return;
+        }
+        self.last_spoke = Some(now);
+        dialogue_box.bind_mut().start(dialogue);
+    }
+}
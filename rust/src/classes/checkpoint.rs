@@ -0,0 +1,64 @@
+use godot::classes::{Area2D, IArea2D};
+use godot::prelude::*;
+
+use super::level_state::LevelState;
+
+/// Marks a respawn point for the player. Reaching it makes deaths return the
+/// player here instead of to the start of the level.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+pub struct Checkpoint {
+    base: Base<Area2D>,
+    /// Order of this checkpoint along the level. Going back to one with a
+    /// lower index than the last one reached doesn't move the respawn point.
+    #[export]
+    index: u32,
+    #[export]
+    respawn_offset: Vector2,
+    #[var]
+    active: bool,
+}
+
+#[godot_api]
+impl IArea2D for Checkpoint {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            index: 0,
+            respawn_offset: Vector2::ZERO,
+            active: false,
+        }
+    }
+
+    fn ready(&mut self) {
+        // Stay active across reloads after a respawn.
+        let Some(level_state) = LevelState::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        let reached = level_state.bind().checkpoint_index();
+        self.active = reached.is_some_and(|reached| reached >= self.index);
+    }
+}
+
+#[godot_api]
+impl Checkpoint {
+    #[signal]
+    fn activated();
+
+    #[func]
+    fn activate(&mut self, body: Gd<Node>) {
+        if self.active || !body.is_class("Player".into()) {
+            return;
+        }
+        let Some(mut level_state) = LevelState::find(body) else {
+            return;
+        };
+        let position = self.base().get_global_position() + self.respawn_offset;
+        if !level_state.bind_mut().set_checkpoint(self.index, position) {
+            return;
+        }
+        self.active = true;
+        self.base_mut().emit_signal("activated".into(), &[]);
+    }
+}
@@ -0,0 +1,309 @@
+use godot::classes::control::LayoutPreset;
+use godot::classes::node::ProcessMode;
+use godot::classes::{
+    Button, CanvasLayer, Control, IControl, InputEvent, Label, PanelContainer,
+    RichTextLabel, VBoxContainer,
+};
+use godot::prelude::*;
+
+use super::{Dialogue, DialogueCondition, DialogueLine, NEXT_LINE};
+use crate::classes::graphs::bar::BarGraph;
+
+/// Group the active dialogue box joins so triggers can reuse it.
+const GROUP: &str = "dialogue_box";
+
+/// Bottom-of-screen box that types out a [`Dialogue`] line by line.
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct DialogueBox {
+    base: Base<Control>,
+    #[export]
+    chars_per_second: f32,
+    dialogue: Option<Gd<Dialogue>>,
+    line: usize,
+    shown: f32,
+    typing: bool,
+    speaker: Option<Gd<Label>>,
+    text: Option<Gd<RichTextLabel>>,
+    choices: Option<Gd<VBoxContainer>>,
+    player: Option<Gd<Node>>,
+}
+
+#[godot_api]
+impl IControl for DialogueBox {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            chars_per_second: 40.,
+            dialogue: None,
+            line: 0,
+            shown: 0.,
+            typing: false,
+            speaker: None,
+            text: None,
+            choices: None,
+            player: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().add_to_group(GROUP.into());
+        self.base_mut()
+            .set_anchors_preset(LayoutPreset::BOTTOM_WIDE);
+        self.base_mut()
+            .set_custom_minimum_size(Vector2::new(0., 160.));
+
+        let mut panel = PanelContainer::new_alloc();
+        panel.set_anchors_preset(LayoutPreset::FULL_RECT);
+        let mut rows = VBoxContainer::new_alloc();
+
+        let speaker = Label::new_alloc();
+        let mut text = RichTextLabel::new_alloc();
+        text.set_use_bbcode(true);
+        text.set_fit_content(true);
+        text.set_scroll_active(false);
+        let choices = VBoxContainer::new_alloc();
+
+        rows.add_child(speaker.clone());
+        rows.add_child(text.clone());
+        rows.add_child(choices.clone());
+        panel.add_child(rows);
+        self.base_mut().add_child(panel);
+
+        self.speaker = Some(speaker);
+        self.text = Some(text);
+        self.choices = Some(choices);
+        self.base_mut().hide();
+    }
+
+    fn process(&mut self, delta: f64) {
+        if !self.typing {
+            return;
+        }
+        let Some(mut text) = self.text.clone() else {
+            return;
+        };
+        let total = text.get_total_character_count() as f32;
+        self.shown =
+            (self.shown + self.chars_per_second * delta as f32).min(total);
+        text.set_visible_characters(self.shown as i32);
+        if self.shown >= total {
+            self.finish_typing();
+        }
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        if !self.base().is_visible()
+            || !event.is_action_pressed("Interact".into())
+        {
+            return;
+        }
+        // Leave the press to the focused choice button as `ui_accept`.
+        if self.awaiting_choice() {
+            return;
+        }
+        let Some(mut viewport) = self.base().get_viewport() else {
+            godot_error!("DialogueBox is not in a viewport");
+            return;
+        };
+        viewport.set_input_as_handled();
+        self.advance();
+    }
+}
+
+#[godot_api]
+impl DialogueBox {
+    #[signal]
+    fn dialogue_started();
+
+    #[signal]
+    fn dialogue_finished();
+
+    /// Returns the dialogue box in the current scene, adding one on its own
+    /// canvas layer if the level doesn't have one.
+    pub fn find_or_create(node: Gd<Node>) -> Option<Gd<Self>> {
+        let Some(mut tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return None;
+        };
+        if let Some(existing) = tree.get_first_node_in_group(GROUP.into()) {
+            return existing.try_cast::<Self>().ok();
+        }
+        let Some(mut scene) = tree.get_current_scene() else {
+            godot_error!("Could not get current scene");
+            return None;
+        };
+        let mut layer = CanvasLayer::new_alloc();
+        let dialogue_box = Self::new_alloc();
+        layer.add_child(dialogue_box.clone());
+        scene.add_child(layer);
+        Some(dialogue_box)
+    }
+
+    /// Whether a dialogue is currently being shown.
+    pub fn is_open(&self) -> bool { self.base().is_visible() }
+
+    pub fn start(&mut self, dialogue: Gd<Dialogue>) {
+        self.dialogue = Some(dialogue);
+        self.freeze_player(true);
+        self.base_mut().show();
+        self.base_mut().emit_signal("dialogue_started".into(), &[]);
+        self.show_line(0);
+    }
+
+    #[func]
+    fn choose(&mut self, next: i32) { self.go_to(next); }
+
+    fn line(&self, index: usize) -> Option<Gd<DialogueLine>> {
+        let dialogue = self.dialogue.as_ref()?;
+        let lines = dialogue.bind().lines.clone();
+        lines.get(index)
+    }
+
+    fn condition_holds(
+        &self,
+        condition: &Option<Gd<DialogueCondition>>,
+    ) -> bool {
+        let Some(condition) = condition else {
+            return true;
+        };
+        let bar_graph = BarGraph::find(self.base().clone().upcast());
+        condition.bind().holds(bar_graph.as_ref())
+    }
+
+    fn show_line(&mut self, mut index: usize) {
+        // Skip forward past any lines whose condition doesn't hold.
+        let line = loop {
+            let Some(line) = self.line(index) else {
+                self.finish();
+                return;
+            };
+            if self.condition_holds(&line.bind().condition) {
+                break line;
+            }
+            index += 1;
+        };
+        self.line = index;
+        self.shown = 0.;
+        self.typing = true;
+
+        let line = line.bind();
+        if let Some(ref mut speaker) = self.speaker {
+            speaker.set_text(line.speaker.clone());
+        }
+        if let Some(ref mut text) = self.text {
+            text.set_text(line.text.clone());
+            text.set_visible_characters(0);
+        }
+        if let Some(ref mut choices) = self.choices {
+            for mut child in choices.get_children().iter_shared() {
+                child.queue_free();
+            }
+        }
+    }
+
+    fn show_choices(&mut self) {
+        let Some(line) = self.line(self.line) else {
+            return;
+        };
+        let this = self.to_gd();
+        let choices: Vec<_> = line
+            .bind()
+            .choices
+            .iter_shared()
+            .filter(|choice| self.condition_holds(&choice.bind().condition))
+            .collect();
+        let Some(ref mut container) = self.choices else {
+            return;
+        };
+        let mut first = true;
+        for choice in choices {
+            let choice = choice.bind();
+            let mut button = Button::new_alloc();
+            button.set_text(choice.text.clone());
+            button.connect(
+                "pressed".into(),
+                Callable::from_object_method(&this, "choose")
+                    .bindv(varray![choice.next]),
+            );
+            container.add_child(button.clone());
+            if first {
+                button.grab_focus();
+                first = false;
+            }
+        }
+    }
+
+    fn advance(&mut self) {
+        if self.typing {
+            // First press skips the typewriter.
+            self.finish_typing();
+            return;
+        }
+        let Some(line) = self.line(self.line) else {
+            self.finish();
+            return;
+        };
+        if self.awaiting_choice() {
+            return;
+        }
+        let next = line.bind().next;
+        self.go_to(next);
+    }
+
+    /// Whether the current line is fully shown and waiting on one of its
+    /// choices.
+    fn awaiting_choice(&self) -> bool {
+        !self.typing
+            && self
+                .line(self.line)
+                .is_some_and(|line| !line.bind().choices.is_empty())
+    }
+
+    fn finish_typing(&mut self) {
+        self.typing = false;
+        if let Some(ref mut text) = self.text {
+            text.set_visible_characters(-1);
+        }
+        self.show_choices();
+    }
+
+    /// Follows a line's or choice's `next`.
+    fn go_to(&mut self, next: i32) {
+        match next {
+            NEXT_LINE => self.show_line(self.line + 1),
+            next if next < 0 => self.finish(),
+            next => self.show_line(next as usize),
+        }
+    }
+
+    fn finish(&mut self) {
+        self.dialogue = None;
+        self.typing = false;
+        self.freeze_player(false);
+        self.base_mut().hide();
+        self.base_mut().emit_signal("dialogue_finished".into(), &[]);
+    }
+
+    /// Stops the player moving or interacting while the dialogue is open.
+    fn freeze_player(&mut self, frozen: bool) {
+        if frozen {
+            let Some(mut tree) = self.base().get_tree() else {
+                return;
+            };
+            self.player = tree.get_first_node_in_group("player".into());
+        }
+        let Some(ref mut player) = self.player else {
+            return;
+        };
+        let mode = if frozen {
+            ProcessMode::DISABLED
+        } else {
+            ProcessMode::INHERIT
+        };
+        player.set_process_mode(mode);
+        if !frozen {
+            self.player = None;
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use godot::classes::{Area2D, IArea2D, IResource, Resource};
+use godot::prelude::*;
+
+use super::graphs::bar::BarGraph;
+use super::interactable::{self, Interactable};
+
+pub mod dialogue_box;
+
+/// `next` of a line or choice that carries on with the line after the
+/// current one.
+pub const NEXT_LINE: i32 = -1;
+/// `next` of a line or choice that ends the dialogue.
+pub const END: i32 = -2;
+
+use dialogue_box::DialogueBox;
+
+/// Gate on a bar's value, used to pick dialogue lines and choices based on
+/// how the graph currently looks.
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct DialogueCondition {
+    #[export]
+    bar_index: u32,
+    #[export]
+    min_value: f32,
+    #[export]
+    max_value: f32,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for DialogueCondition {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            bar_index: 0,
+            min_value: f32::MIN,
+            max_value: f32::MAX,
+            base,
+        }
+    }
+}
+
+impl DialogueCondition {
+    pub fn holds(&self, bar_graph: Option<&Gd<BarGraph>>) -> bool {
+        let Some(bar_graph) = bar_graph else {
+            return false;
+        };
+        let Some(value) = bar_graph.bind().bar_value(self.bar_index) else {
+            return false;
+        };
+        (self.min_value..=self.max_value).contains(&value)
+    }
+}
+
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct DialogueChoice {
+    #[export]
+    text: GString,
+    /// Index of the line this choice leads to, [`NEXT_LINE`] or [`END`].
+    #[export]
+    next: i32,
+    /// Choice is only offered while this holds.
+    #[export]
+    condition: Option<Gd<DialogueCondition>>,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for DialogueChoice {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            text: GString::new(),
+            next: NEXT_LINE,
+            condition: None,
+            base,
+        }
+    }
+}
+
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct DialogueLine {
+    #[export]
+    speaker: GString,
+    #[export(multiline)]
+    text: GString,
+    /// Offered once the line has finished typing. Without choices the
+    /// dialogue carries on to `next`.
+    #[export]
+    choices: Array<Gd<DialogueChoice>>,
+    /// Index of the following line, [`NEXT_LINE`] or [`END`].
+    #[export]
+    next: i32,
+    /// Line is skipped unless this holds.
+    #[export]
+    condition: Option<Gd<DialogueCondition>>,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for DialogueLine {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            speaker: GString::new(),
+            text: GString::new(),
+            choices: Array::new(),
+            next: NEXT_LINE,
+            condition: None,
+            base,
+        }
+    }
+}
+
+#[derive(GodotClass)]
+#[class(tool, init, base=Resource)]
+pub struct Dialogue {
+    #[export]
+    lines: Array<Gd<DialogueLine>>,
+    base: Base<Resource>,
+}
+
+/// Starts a [`Dialogue`] when the level starts, when the player walks in,
+/// or, with `interactable` set, when the player interacts with it.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct DialogueTrigger {
+    base: Base<Area2D>,
+    #[export]
+    dialogue: Option<Gd<Dialogue>>,
+    #[export]
+    on_level_start: bool,
+    #[export]
+    interactable: bool,
+    #[export]
+    prompt: GString,
+    /// Only ever play the dialogue once per visit to the level.
+    #[export]
+    once: bool,
+    played: bool,
+}
+
+#[godot_api]
+impl IArea2D for DialogueTrigger {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            dialogue: None,
+            on_level_start: false,
+            interactable: false,
+            prompt: "PROMPT_TALK".into(),
+            once: true,
+            played: false,
+        }
+    }
+
+    fn ready(&mut self) {
+        if self.interactable {
+            self.base_mut().add_to_group(interactable::GROUP.into());
+        }
+        if self.on_level_start {
+            self.base_mut().call_deferred("start".into(), &[]);
+        }
+    }
+}
+
+impl Interactable for DialogueTrigger {
+    fn prompt(&self) -> GString { self.prompt.clone() }
+
+    fn interact(&mut self, _player: Gd<Node>) { self.start(); }
+}
+
+#[godot_api]
+impl DialogueTrigger {
+    #[func]
+    fn get_prompt(&self) -> GString { Interactable::prompt(self) }
+
+    #[func]
+    fn interact(&mut self, player: Gd<Node>) {
+        Interactable::interact(self, player);
+    }
+
+    #[func]
+    fn body_entered(&mut self, body: Gd<Node>) {
+        if self.interactable || !body.is_class("Player".into()) {
+            return;
+        }
+        self.start();
+    }
+
+    #[func]
+    fn start(&mut self) {
+        if self.once && self.played {
+            return;
+        }
+        let Some(ref dialogue) = self.dialogue else {
+            godot_error!("No Dialogue was set for this trigger!");
+            return;
+        };
+        let dialogue = dialogue.clone();
+        let Some(mut dialogue_box) =
+            DialogueBox::find_or_create(self.base().clone().upcast())
+        else {
+            return;
+        };
+        self.played = true;
+        dialogue_box.bind_mut().start(dialogue);
+    }
+}
@@ -3,14 +3,27 @@ use godot::{
     prelude::*,
 };
 
+use super::interactable::{self, Interactable};
+use super::replay::ReplayRecorder;
+use super::results::Results;
+use super::scene_manager::SceneManager;
+use super::speedrun::Speedrun;
+
 #[derive(GodotClass)]
 #[class(base=Area2D)]
 pub struct Door {
     base: Base<Area2D>,
     #[export]
     dest_scene: Option<Gd<PackedScene>>,
+    /// Used instead of `dest_scene` when set, letting the destination load in
+    /// the background while the level is being played.
+    #[export(file = "*.tscn")]
+    dest_path: GString,
     #[export]
     locked: bool,
+    /// Show the results screen before leaving a level through this door.
+    #[export]
+    show_results: bool,
 }
 
 #[godot_api]
@@ -19,25 +32,101 @@ impl IArea2D for Door {
         Self {
             base,
             dest_scene: None,
+            dest_path: GString::new(),
             locked: false,
+            show_results: true,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().add_to_group(interactable::GROUP.into());
+        if self.dest_path.is_empty() {
+            return;
+        }
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        scene_manager.bind_mut().preload(self.dest_path.clone());
+    }
+}
+
+impl Interactable for Door {
+    fn prompt(&self) -> GString {
+        if self.locked {
+            "PROMPT_UNLOCK".into()
+        } else {
+            "PROMPT_ENTER".into()
         }
     }
+
+    fn interact(&mut self, player: Gd<Node>) {
+        if self.locked {
+            let mut player = player;
+            let has_key: bool = player.call("get_has_key".into(), &[]).to();
+            if !has_key {
+                return;
+            }
+        }
+        self.enter_door();
+    }
 }
 
 #[godot_api]
 impl Door {
+    #[func]
+    fn get_prompt(&self) -> GString { Interactable::prompt(self) }
+
+    #[func]
+    fn interact(&mut self, player: Gd<Node>) {
+        Interactable::interact(self, player);
+    }
+
     #[func]
     fn enter_door(&mut self) {
-        let Some(mut tree) = self.base_mut().get_tree() else {
-            godot_error!("Could not get scene tree!");
+        let Some(scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
             return;
         };
+        if scene_manager.bind().in_level() {
+            ReplayRecorder::complete(self.base().clone().upcast());
+        }
+        // Speedruns go straight on to the next level.
+        let speedrun = Speedrun::is_running(self.base().clone().upcast());
+        if self.show_results && !speedrun && scene_manager.bind().in_level() {
+            Results::open(
+                self.base().clone().upcast(),
+                Callable::from_object_method(&self.to_gd(), "leave"),
+            );
+            return;
+        }
+        self.leave();
+    }
+
+    #[func]
+    fn leave(&mut self) {
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+
+        if !self.dest_path.is_empty() {
+            scene_manager
+                .bind_mut()
+                .change_scene(self.dest_path.clone());
+            return;
+        }
 
         let Some(ref scene) = self.dest_scene else {
             godot_error!("No scene was set for this door!");
             return;
         };
 
-        tree.change_scene_to_packed(scene);
+        scene_manager
+            .bind_mut()
+            .change_scene_to_packed(scene.clone());
     }
 }
@@ -0,0 +1,192 @@
+use godot::prelude::*;
+
+/// How bar values map onto bar heights along a `BarGraph`'s y axis.
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum AxisScale {
+    Linear,
+    /// Compresses large values, so a bar ten times bigger is only about
+    /// twice as tall with base 10.
+    Logarithmic,
+    /// Straight lines between `breakpoints`, continuing the last one.
+    Piecewise,
+}
+
+/// The y axis of a bar graph, turning data values into the heights bars are
+/// drawn and collide at. A value of 1 is a bar's resting height on the
+/// linear and logarithmic scales, and `zoom` stretches every bar without
+/// touching the data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Axis {
+    pub scale: AxisScale,
+    pub log_base: f32,
+    /// `(value, height)` points for the piecewise scale, after an implicit
+    /// `(0, 0)` that a breakpoint at value 0 replaces.
+    pub breakpoints: Vec<(f32, f32)>,
+    pub zoom: f32,
+}
+
+impl Default for Axis {
+    fn default() -> Self {
+        Self {
+            scale: AxisScale::Linear,
+            log_base: 10.,
+            breakpoints: Vec::new(),
+            zoom: 1.,
+        }
+    }
+}
+
+impl Axis {
+    /// Height a bar with `value` is drawn at.
+    pub fn height(&self, value: f32) -> f32 {
+        let height = match self.scale {
+            AxisScale::Linear => value,
+            AxisScale::Logarithmic => self.logarithmic(value),
+            AxisScale::Piecewise => self.piecewise(value),
+        };
+        height * self.zoom
+    }
+
+    /// `log_base(1 + value * (base - 1))`, which is 0 at 0 and 1 at 1 like
+    /// the linear scale, and falls back to linear for bases it can't use.
+    fn logarithmic(&self, value: f32) -> f32 {
+        let base = self.log_base;
+        if !base.is_finite() || base <= 1. {
+            return value;
+        }
+        (1. + value.max(0.) * (base - 1.)).log(base)
+    }
+
+    fn piecewise(&self, value: f32) -> f32 {
+        // The sort is stable and dedup keeps the first of each value, so
+        // the origin goes last to lose to a breakpoint at 0.
+        let mut points = self.breakpoints.clone();
+        points.push((0., 0.));
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|a, b| a.0 == b.0);
+        if points.len() < 2 {
+            return value;
+        }
+
+        // Extrapolate past the ends with the slope of the nearest segment.
+        let segment = points
+            .windows(2)
+            .position(|pair| value <= pair[1].0)
+            .unwrap_or(points.len() - 2);
+        let (from, to) = (points[segment], points[segment + 1]);
+        let t = (value - from.0) / (to.0 - from.0);
+        from.1 + (to.1 - from.1) * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis(scale: AxisScale) -> Axis {
+        Axis {
+            scale,
+            ..Default::default()
+        }
+    }
+
+    fn piecewise(breakpoints: &[(f32, f32)]) -> Axis {
+        Axis {
+            breakpoints: breakpoints.to_vec(),
+            ..axis(AxisScale::Piecewise)
+        }
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn linear_heights_are_the_values() {
+        let linear = axis(AxisScale::Linear);
+        assert_eq!(linear.height(0.), 0.);
+        assert_eq!(linear.height(3.5), 3.5);
+        assert_eq!(linear.height(-2.), -2.);
+    }
+
+    #[test]
+    fn logarithmic_heights_match_linear_at_zero_and_one() {
+        let log = axis(AxisScale::Logarithmic);
+        assert_close(log.height(0.), 0.);
+        assert_close(log.height(1.), 1.);
+        assert_close(log.height(10.), 91f32.log10());
+        assert!(log.height(100.) < 3.);
+    }
+
+    #[test]
+    fn logarithmic_heights_never_go_below_zero() {
+        assert_close(axis(AxisScale::Logarithmic).height(-5.), 0.);
+    }
+
+    #[test]
+    fn unusable_log_bases_fall_back_to_linear() {
+        for log_base in [1., 0.5, -10., f32::NAN, f32::INFINITY] {
+            let log = Axis {
+                log_base,
+                ..axis(AxisScale::Logarithmic)
+            };
+            assert_eq!(log.height(7.), 7., "base {}", log_base);
+        }
+    }
+
+    #[test]
+    fn piecewise_heights_follow_the_breakpoints() {
+        let axis = piecewise(&[(10., 5.), (20., 25.)]);
+        assert_close(axis.height(4.), 2.);
+        assert_close(axis.height(10.), 5.);
+        assert_close(axis.height(15.), 15.);
+    }
+
+    #[test]
+    fn piecewise_heights_continue_the_end_segments() {
+        let axis = piecewise(&[(10., 5.), (20., 25.)]);
+        assert_close(axis.height(30.), 45.);
+        assert_close(axis.height(-2.), -1.);
+    }
+
+    #[test]
+    fn piecewise_breakpoints_can_come_in_any_order() {
+        let sorted = piecewise(&[(10., 5.), (20., 25.)]);
+        let unsorted = piecewise(&[(20., 25.), (10., 5.)]);
+        assert_eq!(sorted.height(12.), unsorted.height(12.));
+    }
+
+    #[test]
+    fn a_breakpoint_at_zero_replaces_the_origin() {
+        let axis = piecewise(&[(0., 2.), (10., 12.)]);
+        assert_close(axis.height(0.), 2.);
+        assert_close(axis.height(5.), 7.);
+    }
+
+    #[test]
+    fn piecewise_without_breakpoints_is_linear() {
+        assert_eq!(piecewise(&[]).height(4.), 4.);
+        assert_eq!(piecewise(&[(0., 3.)]).height(4.), 4.);
+    }
+
+    #[test]
+    fn zoom_stretches_every_scale() {
+        for scale in [
+            AxisScale::Linear,
+            AxisScale::Logarithmic,
+            AxisScale::Piecewise,
+        ] {
+            let mut axis = piecewise(&[(10., 5.)]);
+            axis.scale = scale;
+            let height = axis.height(4.);
+            axis.zoom = 2.;
+            assert_close(axis.height(4.), height * 2.);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use godot::classes::control::MouseFilter;
+use godot::classes::node::ProcessMode;
 use godot::classes::{
     BoxMesh, CollisionShape2D, Gradient, GradientTexture1D, INode2D,
     IStaticBody2D, InputEvent, Label, MeshInstance2D, Node2D, RectangleShape2D,
@@ -7,6 +9,10 @@ use godot::global::HorizontalAlignment;
 use godot::global::Key;
 use godot::prelude::*;
 
+use super::axis::{Axis, AxisScale};
+use super::unit::{ValueFormat, ValueUnit};
+use crate::classes::locale::tr;
+
 #[derive(GodotClass)]
 #[class(base=Node2D)]
 pub struct BarGraph {
@@ -17,6 +23,45 @@ pub struct BarGraph {
     spacing: f32,
     #[export]
     bar_details: Array<Gd<BarDetails>>,
+    /// Show each bar's value above it.
+    #[export]
+    show_values: bool,
+    #[export]
+    unit: ValueUnit,
+    /// Amount a bar stands for per unit of height, e.g. 1000 for a graph
+    /// in thousands of dollars.
+    #[export]
+    value_scale: f32,
+    #[export]
+    currency_symbol: GString,
+    #[export]
+    value_decimals: u32,
+    /// Write large values as 1.2K, 3.4M and so on.
+    #[export]
+    abbreviate_values: bool,
+    #[export]
+    axis_scale: AxisScale,
+    #[export]
+    log_base: f32,
+    /// Points of the piecewise scale, with the bar value in `x` and the
+    /// height it's drawn at in `y`.
+    #[export]
+    breakpoints: PackedVector2Array,
+    /// Stretches every bar along the axis without changing its value.
+    #[export]
+    zoom: f32,
+    /// Let the player change `zoom` with `ZoomIn` and `ZoomOut`.
+    #[export]
+    zoomable: bool,
+    #[export]
+    zoom_step: f32,
+    #[export]
+    min_zoom: f32,
+    #[export]
+    max_zoom: f32,
+    /// Set while `bar_changed` is emitted for a change played back from a
+    /// replay rather than made by the player.
+    playing_back: bool,
 }
 
 #[godot_api]
@@ -27,10 +72,28 @@ impl INode2D for BarGraph {
             offset: 0.,
             spacing: 50.,
             bar_details: Array::new(),
+            show_values: true,
+            unit: ValueUnit::Plain,
+            value_scale: 1.,
+            currency_symbol: "$".into(),
+            value_decimals: 2,
+            abbreviate_values: true,
+            axis_scale: AxisScale::Linear,
+            log_base: 10.,
+            breakpoints: PackedVector2Array::new(),
+            zoom: 1.,
+            zoomable: false,
+            zoom_step: 0.25,
+            min_zoom: 0.25,
+            max_zoom: 2.,
+            playing_back: false,
         }
     }
 
     fn ready(&mut self) {
+        // Bars poll the keyboard in `process`, so make sure the whole graph
+        // stops when the game is paused.
+        self.base_mut().set_process_mode(ProcessMode::PAUSABLE);
         if self.bar_details.len() > 25 {
             godot_error!("Bar Count cannot exceed 25!");
             return;
@@ -42,13 +105,27 @@ impl INode2D for BarGraph {
     }
 
     fn input(&mut self, event: Gd<InputEvent>) {
+        if self.zoomable {
+            let step = if event.is_action_pressed("ZoomIn".into()) {
+                self.zoom_step
+            } else if event.is_action_pressed("ZoomOut".into()) {
+                -self.zoom_step
+            } else {
+                0.
+            };
+            if step != 0. {
+                self.rescale(self.zoom + step);
+                return;
+            }
+        }
         let change = 0.25;
         #[cfg(debug_assertions)]
         if event.is_action_pressed("DebugUp".into()) {
             let children = self.base_mut().get_children();
             for child in children.iter_shared() {
                 let child = child.try_cast::<Bar>().unwrap();
-                Bar::increase_height(child, change);
+                Bar::increase_height(child.clone(), change);
+                self.notify(&child);
             }
         }
         #[cfg(debug_assertions)]
@@ -56,7 +133,8 @@ impl INode2D for BarGraph {
             let children = self.base_mut().get_children();
             for child in children.iter_shared() {
                 let child = child.try_cast::<Bar>().unwrap();
-                Bar::decrease_height(child, change);
+                Bar::decrease_height(child.clone(), change);
+                self.notify(&child);
             }
         }
     }
@@ -64,6 +142,115 @@ impl INode2D for BarGraph {
 
 #[godot_api]
 impl BarGraph {
+    #[signal]
+    fn bar_changed(index: u32, value: f32);
+
+    #[signal]
+    fn axis_changed(zoom: f32);
+
+    /// Finds the `BarGraph` of the level `node` is in, which by convention
+    /// lives at `Graph/BarGraph` under the scene root.
+    pub fn find(node: Gd<Node>) -> Option<Gd<Self>> {
+        let Some(tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return None;
+        };
+        let Some(scene) = tree.get_current_scene() else {
+            godot_error!("Could not get current scene");
+            return None;
+        };
+        scene.try_get_node_as::<Self>("Graph/BarGraph")
+    }
+
+    /// Adds a bar to be created when the graph is ready, for graphs built in
+    /// code rather than in the editor.
+    pub fn add_bar_details(&mut self, name: &str, height: f32) {
+        let mut details = BarDetails::new_gd();
+        {
+            let mut details = details.bind_mut();
+            details.name = name.into();
+            details.height = height;
+        }
+        self.bar_details.push(details);
+    }
+
+    pub fn axis(&self) -> Axis {
+        Axis {
+            scale: self.axis_scale,
+            log_base: self.log_base,
+            breakpoints: self
+                .breakpoints
+                .as_slice()
+                .iter()
+                .map(|point| (point.x, point.y))
+                .collect(),
+            zoom: self.zoom,
+        }
+    }
+
+    /// Zooms the axis to `zoom`, within `min_zoom..=max_zoom`, moving the
+    /// bars while their values stay the same.
+    #[func]
+    fn rescale(&mut self, zoom: f32) {
+        let zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+        if zoom == self.zoom {
+            return;
+        }
+        self.zoom = zoom;
+        let axis = self.axis();
+        for mut bar in self.bars() {
+            bar.bind_mut().set_axis(axis.clone());
+        }
+        self.base_mut()
+            .emit_signal("axis_changed".into(), &[zoom.to_variant()]);
+    }
+
+    pub fn value_format(&self) -> ValueFormat {
+        ValueFormat {
+            unit: self.unit,
+            scale: self.value_scale as f64,
+            currency_symbol: self.currency_symbol.to_string(),
+            decimals: self.value_decimals as usize,
+            abbreviate: self.abbreviate_values,
+        }
+    }
+
+    /// Writes out a bar value in the graph's unit, e.g. "$1.2K".
+    #[func]
+    fn format_value(&self, value: f32) -> GString {
+        self.value_format().format(value).into()
+    }
+
+    pub fn bars(&self) -> Vec<Gd<Bar>> {
+        self.base()
+            .get_children()
+            .iter_shared()
+            .filter_map(|child| child.try_cast::<Bar>().ok())
+            .collect()
+    }
+
+    pub fn bar_value(&self, index: u32) -> Option<f32> {
+        let bar = self.bars().into_iter().nth(index as usize)?;
+        let value = bar.bind().height;
+        Some(value)
+    }
+
+    #[func]
+    fn get_bar_value(&self, index: u32) -> f32 {
+        self.bar_value(index).unwrap_or(0.)
+    }
+
+    fn notify(&mut self, bar: &Gd<Bar>) {
+        let (index, value) = {
+            let bar = bar.bind();
+            (bar.index, bar.height)
+        };
+        self.base_mut().emit_signal(
+            "bar_changed".into(),
+            &[index.to_variant(), value.to_variant()],
+        );
+    }
+
     fn create_bar(&mut self, index: u32, mut details: Gd<BarDetails>) {
         let Some(graph) = self.base().get_parent() else {
             godot_error!("Graph parent did not exist");
@@ -86,6 +273,11 @@ impl BarGraph {
 
         let mut bar =
             Bar::create_with_height_index_and_name(index, height, &name);
+        {
+            let mut bar = bar.bind_mut();
+            bar.value_format = self.show_values.then(|| self.value_format());
+            bar.axis = self.axis();
+        }
 
         {
             let graph_transform = self.base().get_transform();
@@ -136,7 +328,8 @@ impl BarGraph {
 
         let bar = bar.try_cast::<Bar>().unwrap();
 
-        Bar::increase_height(bar, amount as f32 * 0.25);
+        Bar::increase_height(bar.clone(), amount as f32 * 0.25);
+        self.notify(&bar);
     }
 
     #[func]
@@ -147,10 +340,30 @@ impl BarGraph {
             return;
         };
 
-        let bar = bar.try_cast::<Bar>().unwrap();
+        let mut bar = bar.try_cast::<Bar>().unwrap();
 
-        Bar::set_height(bar, amount as f32);
+        if bar.bind_mut().set_value(amount as f32) {
+            self.notify(&bar);
+        }
     }
+
+    /// Sets a bar's displayed value directly when playing back a replay.
+    /// Handlers of the resulting `bar_changed` can tell it apart from the
+    /// player's own changes with [`is_playing_back`](Self::is_playing_back).
+    pub fn set_bar_value(&mut self, index: u32, value: f32) {
+        let Some(mut bar) = self.bars().into_iter().nth(index as usize) else {
+            godot_error!("Could not get bar with Index {}", index);
+            return;
+        };
+        if bar.bind_mut().set_value(value) {
+            self.playing_back = true;
+            self.notify(&bar);
+            self.playing_back = false;
+        }
+    }
+
+    /// Whether the `bar_changed` being emitted comes from a replay.
+    pub fn is_playing_back(&self) -> bool { self.playing_back }
 }
 
 #[derive(GodotClass)]
@@ -170,6 +383,9 @@ pub struct Bar {
     name: String,
     index: u32,
     height: f32,
+    value_format: Option<ValueFormat>,
+    value_label: Option<Gd<Label>>,
+    axis: Axis,
 }
 
 #[godot_api]
@@ -180,6 +396,9 @@ impl IStaticBody2D for Bar {
             name: "".into(),
             index: 0,
             height: 1.,
+            value_format: None,
+            value_label: None,
+            axis: Axis::default(),
         }
     }
 
@@ -230,12 +449,23 @@ impl IStaticBody2D for Bar {
         // self.base_mut().add_child(mesh_instance);
 
         let scale = self.base().get_scale();
-        let height = self.height;
-        container.set_scale(scale + Vector2::new(0.0, height));
-        container.translate(Vector2::new(0.0, height / -0.08));
+        container.set_scale(scale);
+        // From here on `height` is the displayed value rather than the offset
+        // from the bar's own scale it was created with.
+        self.height += scale.y;
 
         self.base_mut().add_child(container);
         self.base_mut().add_child(text);
+        if self.value_format.is_some() {
+            let mut value_label = Label::new_alloc();
+            value_label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+            // Tooltips need the label to see the mouse.
+            value_label.set_mouse_filter(MouseFilter::PASS);
+            value_label.set_size(Vector2::new(58., 23.));
+            self.base_mut().add_child(value_label.clone());
+            self.value_label = Some(value_label);
+        }
+        self.apply_height();
     }
 
     fn process(&mut self, _delta: f64) {
@@ -253,12 +483,14 @@ impl IStaticBody2D for Bar {
             9 => Key::KEY_0,
             _ => Key::HOME,
         };
-        if input.is_key_pressed(Key::Q) {
-            if input.is_key_pressed(key) {
-                Bar::increase_height(self.to_gd(), 0.25);
-            }
-        } else if input.is_key_pressed(key) {
-            Bar::decrease_height(self.to_gd(), 0.25);
+        let change = if input.is_key_pressed(Key::Q) {
+            0.25
+        } else {
+            -0.25
+        };
+        if input.is_key_pressed(key) {
+            self.set_value(self.height + change);
+            self.notify_graph();
         }
     }
 }
@@ -267,43 +499,87 @@ impl IStaticBody2D for Bar {
 impl Bar {
     #[func]
     fn set_height(mut object: Gd<Self>, height: f32) {
-        let Some(node) = object.get_child(0) else {
+        object.bind_mut().set_value(height);
+    }
+
+    #[func]
+    fn increase_height(mut object: Gd<Self>, height: f32) {
+        let mut bar = object.bind_mut();
+        let value = bar.height + height;
+        bar.set_value(value);
+    }
+
+    #[func]
+    fn decrease_height(mut object: Gd<Self>, height: f32) {
+        let mut bar = object.bind_mut();
+        let value = bar.height - height;
+        bar.set_value(value);
+    }
+
+    pub fn display_name(&self) -> &str { &self.name }
+
+    pub fn value(&self) -> f32 { self.height }
+
+    pub fn set_axis(&mut self, axis: Axis) {
+        self.axis = axis;
+        self.apply_height();
+    }
+
+    /// Sets the displayed value of the bar, returning whether it changed.
+    pub fn set_value(&mut self, value: f32) -> bool {
+        if self.height == value {
+            return false;
+        }
+        self.height = value;
+        self.apply_height();
+        true
+    }
+
+    fn apply_height(&mut self) {
+        let Some(node) = self.base().get_child(0) else {
             godot_error!("Could not get first child of Bar");
             return;
         };
         let mut node = node.try_cast::<StaticBody2D>().unwrap();
+        // `height` is the bar's value; the axis decides how tall that is.
+        let shown = self.axis.height(self.height);
         let mut scale = node.get_scale();
-        scale.y = height;
+        scale.y = shown;
         node.set_scale(scale);
         let mut pos = node.get_position();
-        pos.y = 0.;
+        pos.y = (shown - 1.) / -0.08;
         node.set_position(pos);
-        let mut transform = node.get_transform();
-        node.translate(Vector2::new(0.0, (height - 1.) / -0.08));
-    }
 
-    #[func]
-    fn increase_height(mut object: Gd<Self>, height: f32) {
-        let Some(node) = object.get_child(0) else {
-            godot_error!("Could not get first child of Bar");
-            return;
-        };
-        let mut node = node.try_cast::<StaticBody2D>().unwrap();
-        let scale = node.get_scale();
-        node.set_scale(scale + Vector2::new(0.0, height));
-        node.translate(Vector2::new(0.0, height / -0.08));
+        if let (Some(format), Some(value_label)) =
+            (&self.value_format, &mut self.value_label)
+        {
+            let top = 12.5 - 25. * shown;
+            value_label.set_text(format.format(self.height).into());
+            let tooltip = format!(
+                "{}: {}",
+                tr(&self.name),
+                format.format_full(self.height)
+            );
+            value_label.set_tooltip_text(tooltip.into());
+            value_label.set_position(Vector2::new(-29., top - 25.));
+        }
     }
 
-    #[func]
-    fn decrease_height(mut object: Gd<Self>, height: f32) {
-        let Some(node) = object.get_child(0) else {
-            godot_error!("Could not get first child of Bar");
+    /// Lets the graph know about a change made by the bar itself. Called
+    /// while the bar is bound, so the signal waits until the end of the
+    /// frame to let handlers read this bar.
+    fn notify_graph(&self) {
+        let Some(mut graph) = self.base().get_parent() else {
             return;
         };
-        let mut node = node.try_cast::<StaticBody2D>().unwrap();
-        let scale = node.get_scale();
-        node.set_scale(scale + Vector2::new(0.0, -height));
-        node.translate(Vector2::new(0.0, -height / -0.08));
+        graph.call_deferred(
+            "emit_signal".into(),
+            &[
+                "bar_changed".to_variant(),
+                self.index.to_variant(),
+                self.height.to_variant(),
+            ],
+        );
     }
 
     fn create_with_height_and_index(index: u32, height: f32) -> Gd<Self> {
@@ -312,6 +588,9 @@ impl Bar {
             height,
             index,
             name: "".into(),
+            value_format: None,
+            value_label: None,
+            axis: Axis::default(),
         })
     }
 
@@ -325,6 +604,9 @@ impl Bar {
             height,
             index,
             name: name.into(),
+            value_format: None,
+            value_label: None,
+            axis: Axis::default(),
         })
     }
 }
@@ -0,0 +1,100 @@
+use godot::classes::control::LayoutPreset;
+use godot::classes::{CanvasLayer, ICanvasLayer, Label, PanelContainer};
+use godot::prelude::*;
+
+use super::bar::BarGraph;
+use crate::classes::locale::{format_number, tr, tr_args};
+
+/// Corner readout of every bar's value in the graph's unit, like the key
+/// of a report.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer)]
+struct GraphHud {
+    base: Base<CanvasLayer>,
+    bar_graph: Option<Gd<BarGraph>>,
+    label: Option<Gd<Label>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for GraphHud {
+    fn init(base: Base<CanvasLayer>) -> Self {
+        Self {
+            base,
+            bar_graph: None,
+            label: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let mut panel = PanelContainer::new_alloc();
+        panel.set_anchors_preset(LayoutPreset::TOP_RIGHT);
+        panel.set_position(Vector2::new(-216., 16.));
+        panel.set_custom_minimum_size(Vector2::new(200., 0.));
+        let label = Label::new_alloc();
+        panel.add_child(label.clone());
+        self.base_mut().add_child(panel);
+        self.label = Some(label);
+
+        let Some(mut bar_graph) = BarGraph::find(self.base().clone().upcast())
+        else {
+            self.base_mut().hide();
+            return;
+        };
+        bar_graph.connect(
+            "bar_changed".into(),
+            Callable::from_object_method(&self.to_gd(), "bar_changed"),
+        );
+        bar_graph.connect(
+            "axis_changed".into(),
+            Callable::from_object_method(&self.to_gd(), "axis_changed"),
+        );
+        self.bar_graph = Some(bar_graph);
+        // Bars are only created once the graph is ready.
+        self.base_mut().call_deferred("refresh".into(), &[]);
+    }
+}
+
+#[godot_api]
+impl GraphHud {
+    // The graph can still be bound while it emits these, so the readout,
+    // which reads every bar through it, waits until the end of the frame.
+    #[func]
+    fn bar_changed(&mut self, _index: u32, _value: f32) {
+        self.base_mut().call_deferred("refresh".into(), &[]);
+    }
+
+    #[func]
+    fn axis_changed(&mut self, _zoom: f32) {
+        self.base_mut().call_deferred("refresh".into(), &[]);
+    }
+
+    #[func]
+    fn refresh(&mut self) {
+        let Some(ref bar_graph) = self.bar_graph else {
+            return;
+        };
+        let bar_graph = bar_graph.bind();
+        let format = bar_graph.value_format();
+        let mut lines: Vec<String> = bar_graph
+            .bars()
+            .iter()
+            .enumerate()
+            .map(|(idx, bar)| {
+                let bar = bar.bind();
+                let name = match bar.display_name() {
+                    "" => format!("#{}", idx + 1),
+                    name => tr(name),
+                };
+                format!("{}: {}", name, format.format(bar.value()))
+            })
+            .collect();
+        let zoom = bar_graph.get_zoom();
+        if zoom != 1. {
+            let zoom = format_number(zoom as f64, 2);
+            lines.push(tr_args("HUD_ZOOM", &[&zoom]));
+        }
+        if let Some(ref mut label) = self.label {
+            label.set_text(lines.join("\n").into());
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use godot::classes::{INode2D, Node2D};
 use godot::prelude::*;
 
+pub mod axis;
 pub mod bar;
+mod hud;
+mod platform;
 mod point;
+pub mod unit;
 
 #[derive(GodotClass)]
 #[class(base=Node2D)]
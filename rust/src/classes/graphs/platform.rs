@@ -0,0 +1,113 @@
+use godot::classes::{AnimatableBody2D, IAnimatableBody2D};
+use godot::prelude::*;
+
+use super::bar::BarGraph;
+
+/// Which part of a [`GraphBoundPlatform`] follows its bar.
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum PlatformMode {
+    Position,
+    Rotation,
+    Visibility,
+}
+
+/// Platform that moves, rotates or appears based on the value of a bar in the
+/// level's `BarGraph`, so tuning the graph changes the level geometry.
+#[derive(GodotClass)]
+#[class(base=AnimatableBody2D)]
+struct GraphBoundPlatform {
+    base: Base<AnimatableBody2D>,
+    #[export]
+    bar_index: u32,
+    #[export]
+    mode: PlatformMode,
+    /// Bar value at which the platform sits where it was placed.
+    #[export]
+    base_value: f32,
+    /// Movement per unit of bar value above `base_value`.
+    #[export]
+    offset_per_unit: Vector2,
+    /// Degrees of rotation per unit of bar value above `base_value`.
+    #[export]
+    rotation_per_unit: f32,
+    /// Bar value at or above which the platform is visible and solid.
+    #[export]
+    visible_threshold: f32,
+    /// How quickly the platform catches up with the bar. 0 snaps instantly.
+    #[export]
+    smoothing: f32,
+    bar_graph: Option<Gd<BarGraph>>,
+    start_position: Vector2,
+    start_rotation: f32,
+    collision_layer: u32,
+    value: f32,
+}
+
+#[godot_api]
+impl IAnimatableBody2D for GraphBoundPlatform {
+    fn init(base: Base<AnimatableBody2D>) -> Self {
+        Self {
+            base,
+            bar_index: 0,
+            mode: PlatformMode::Position,
+            base_value: 1.,
+            offset_per_unit: Vector2::new(0., -50.),
+            rotation_per_unit: 15.,
+            visible_threshold: 1.,
+            smoothing: 8.,
+            bar_graph: None,
+            start_position: Vector2::ZERO,
+            start_rotation: 0.,
+            collision_layer: 1,
+            value: 1.,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.start_position = self.base().get_position();
+        self.start_rotation = self.base().get_rotation();
+        self.collision_layer = self.base().get_collision_layer();
+        self.value = self.base_value;
+        self.bar_graph = BarGraph::find(self.base().clone().upcast());
+        if self.bar_graph.is_none() {
+            godot_error!("GraphBoundPlatform could not find a BarGraph");
+        }
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        let Some(ref bar_graph) = self.bar_graph else {
+            return;
+        };
+        let Some(target) = bar_graph.bind().bar_value(self.bar_index) else {
+            return;
+        };
+
+        self.value = if self.smoothing <= 0. {
+            target
+        } else {
+            let weight = (self.smoothing * delta as f32).min(1.);
+            self.value + (target - self.value) * weight
+        };
+
+        let units = self.value - self.base_value;
+        match self.mode {
+            PlatformMode::Position => {
+                let position =
+                    self.start_position + self.offset_per_unit * units;
+                self.base_mut().set_position(position);
+            }
+            PlatformMode::Rotation => {
+                let rotation = self.start_rotation
+                    + (self.rotation_per_unit * units).to_radians();
+                self.base_mut().set_rotation(rotation);
+            }
+            PlatformMode::Visibility => {
+                let shown = target >= self.visible_threshold;
+                let layer = if shown { self.collision_layer } else { 0 };
+                self.base_mut().set_visible(shown);
+                self.base_mut().set_collision_layer(layer);
+            }
+        }
+    }
+}
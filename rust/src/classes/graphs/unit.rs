@@ -0,0 +1,178 @@
+use godot::prelude::*;
+
+use crate::classes::locale::{
+    current_locale, format_number_in, language, separators, tr_args,
+};
+
+/// Abbreviations for large amounts, largest first.
+const SUFFIXES: [(f64, &str); 3] = [(1e9, "B"), (1e6, "M"), (1e3, "K")];
+
+/// What the values of a `BarGraph` measure.
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum ValueUnit {
+    Plain,
+    Currency,
+    Percent,
+    UnitsSold,
+}
+
+/// How bar values are written out in value labels, tooltips and the HUD.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueFormat {
+    pub unit: ValueUnit,
+    /// Amount shown per unit of bar height.
+    pub scale: f64,
+    pub currency_symbol: String,
+    pub decimals: usize,
+    /// Write amounts of a thousand and up as 1.2K, 3.4M and so on.
+    pub abbreviate: bool,
+}
+
+impl Default for ValueFormat {
+    fn default() -> Self {
+        Self {
+            unit: ValueUnit::Plain,
+            scale: 1.,
+            currency_symbol: "$".into(),
+            decimals: 2,
+            abbreviate: true,
+        }
+    }
+}
+
+impl ValueFormat {
+    pub fn format(&self, value: f32) -> String {
+        self.format_in(value, &current_locale(), self.abbreviate)
+    }
+
+    /// Like [`format`](Self::format) but never abbreviated, for tooltips.
+    pub fn format_full(&self, value: f32) -> String {
+        self.format_in(value, &current_locale(), false)
+    }
+
+    pub fn format_in(
+        &self,
+        value: f32,
+        locale: &str,
+        abbreviate: bool,
+    ) -> String {
+        let amount = value as f64 * self.scale;
+        let number = if abbreviate {
+            self.abbreviated(amount, locale)
+        } else {
+            format_number_in(amount, self.decimals, locale)
+        };
+        let english = language(locale) == "en";
+        match self.unit {
+            ValueUnit::Plain => number,
+            ValueUnit::Currency if english => match number.strip_prefix('-') {
+                Some(number) => format!("-{}{}", self.currency_symbol, number),
+                None => format!("{}{}", self.currency_symbol, number),
+            },
+            ValueUnit::Currency => {
+                format!("{}\u{a0}{}", number, self.currency_symbol)
+            }
+            ValueUnit::Percent if english => format!("{}%", number),
+            ValueUnit::Percent => format!("{}\u{a0}%", number),
+            ValueUnit::UnitsSold => tr_args("UNIT_SOLD", &[&number]),
+        }
+    }
+
+    /// `amount` with one decimal and a K, M or B suffix when it's large
+    /// enough, dropping the decimal when it's zero.
+    fn abbreviated(&self, amount: f64, locale: &str) -> String {
+        // Pick the suffix by the rounded amount, so 999,960 is 1M rather
+        // than 1,000K.
+        let Some((threshold, suffix)) =
+            SUFFIXES.iter().find(|(threshold, _)| {
+                (amount.abs() / threshold * 1e4).round() >= 1e4
+            })
+        else {
+            return format_number_in(amount, self.decimals, locale);
+        };
+        let mut number = format_number_in(amount / threshold, 1, locale);
+        let (_, decimal) = separators(locale);
+        if number.ends_with(&format!("{}0", decimal)) {
+            number.truncate(number.len() - decimal.len_utf8() - 1);
+        }
+        number + suffix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(unit: ValueUnit) -> ValueFormat {
+        ValueFormat {
+            unit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn large_amounts_are_abbreviated() {
+        let plain = format(ValueUnit::Plain);
+        assert_eq!(plain.format_in(1500., "en", true), "1.5K");
+        assert_eq!(plain.format_in(2_345_678., "en", true), "2.3M");
+        assert_eq!(plain.format_in(3e9, "en", true), "3B");
+        assert_eq!(plain.format_in(999., "en", true), "999.00");
+        assert_eq!(plain.format_in(1500., "en", false), "1,500.00");
+    }
+
+    #[test]
+    fn amounts_rounding_up_take_the_larger_suffix() {
+        let plain = format(ValueUnit::Plain);
+        assert_eq!(plain.format_in(999_960., "en", true), "1M");
+        assert_eq!(plain.format_in(999_940., "en", true), "999.9K");
+    }
+
+    #[test]
+    fn a_trailing_zero_decimal_is_dropped() {
+        let plain = format(ValueUnit::Plain);
+        assert_eq!(plain.format_in(2_000_000., "en", true), "2M");
+        assert_eq!(plain.format_in(2_000_000., "de", true), "2M");
+        assert_eq!(plain.format_in(2_500_000., "de", true), "2,5M");
+        assert_eq!(plain.format_in(10_000., "fr", true), "10K");
+    }
+
+    #[test]
+    fn values_are_scaled_first() {
+        let revenue = ValueFormat {
+            scale: 100_000.,
+            ..format(ValueUnit::Currency)
+        };
+        assert_eq!(revenue.format_in(4., "en", true), "$400K");
+        assert_eq!(revenue.format_in(12., "en", true), "$1.2M");
+    }
+
+    #[test]
+    fn currency_goes_before_english_numbers_and_after_others() {
+        let currency = format(ValueUnit::Currency);
+        assert_eq!(currency.format_in(1234.5, "en", false), "$1,234.50");
+        assert_eq!(currency.format_in(1234.5, "de", false), "1.234,50\u{a0}$");
+        assert_eq!(
+            currency.format_in(1234.5, "fr", false),
+            "1\u{202f}234,50\u{a0}$"
+        );
+    }
+
+    #[test]
+    fn negative_currency_puts_the_sign_first() {
+        let currency = format(ValueUnit::Currency);
+        assert_eq!(currency.format_in(-1234.5, "en", false), "-$1,234.50");
+        assert_eq!(currency.format_in(-1500., "en", true), "-$1.5K");
+        assert_eq!(currency.format_in(-1500., "de", true), "-1,5K\u{a0}$");
+    }
+
+    #[test]
+    fn percentages_are_spaced_outside_english() {
+        let percent = ValueFormat {
+            decimals: 0,
+            ..format(ValueUnit::Percent)
+        };
+        assert_eq!(percent.format_in(50., "en", false), "50%");
+        assert_eq!(percent.format_in(50., "fr", false), "50\u{a0}%");
+    }
+}
@@ -0,0 +1,260 @@
+use std::f32::consts::TAU;
+
+use godot::classes::{Area2D, IArea2D, Node2D};
+use godot::prelude::*;
+
+use super::player::Player;
+
+/// Damages `body` if it is the player.
+fn hurt(body: Gd<Node>, damage: u32) {
+    if !body.is_class("Player".into()) {
+        return;
+    }
+    Player::damage(body, damage);
+}
+
+/// Hurts whoever is still inside `hazard`. `body_entered` only fires once,
+/// so without this a player still inside when their invulnerability wears
+/// off could stand in it unharmed.
+fn hurt_overlapping(hazard: Gd<Area2D>, damage: u32) {
+    for body in hazard.get_overlapping_bodies().iter_shared() {
+        hurt(body.upcast(), damage);
+    }
+}
+
+/// Routes the hazard's `body_entered` signal to its own `body_entered` func.
+fn connect_hurtbox<T>(hazard: Gd<T>)
+where
+    T: GodotClass + Inherits<Area2D>,
+{
+    let callable = Callable::from_object_method(&hazard, "body_entered");
+    hazard
+        .upcast::<Area2D>()
+        .connect("body_entered".into(), callable);
+}
+
+/// Static hazard that hurts on contact.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct Spikes {
+    base: Base<Area2D>,
+    #[export]
+    damage: u32,
+}
+
+#[godot_api]
+impl IArea2D for Spikes {
+    fn init(base: Base<Area2D>) -> Self { Self { base, damage: 1 } }
+
+    fn ready(&mut self) { connect_hurtbox(self.to_gd()); }
+
+    fn physics_process(&mut self, _delta: f64) {
+        hurt_overlapping(self.base().clone(), self.damage);
+    }
+}
+
+#[godot_api]
+impl Spikes {
+    #[func]
+    fn body_entered(&mut self, body: Gd<Node>) { hurt(body, self.damage); }
+}
+
+/// Moves back and forth along `travel` from where it was placed, easing at
+/// both ends.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct Crusher {
+    base: Base<Area2D>,
+    #[export]
+    damage: u32,
+    #[export]
+    travel: Vector2,
+    /// Seconds for a full down-and-up cycle.
+    #[export]
+    period: f32,
+    start: Vector2,
+    elapsed: f32,
+}
+
+#[godot_api]
+impl IArea2D for Crusher {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            damage: 2,
+            travel: Vector2::new(0., 100.),
+            period: 2.,
+            start: Vector2::ZERO,
+            elapsed: 0.,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.start = self.base().get_position();
+        connect_hurtbox(self.to_gd());
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        hurt_overlapping(self.base().clone(), self.damage);
+        if self.period <= 0. {
+            return;
+        }
+        self.elapsed = (self.elapsed + delta as f32) % self.period;
+        let phase = self.elapsed / self.period;
+        let extent = 0.5 - 0.5 * (TAU * phase).cos();
+        let position = self.start + self.travel * extent;
+        self.base_mut().set_position(position);
+    }
+}
+
+#[godot_api]
+impl Crusher {
+    #[func]
+    fn body_entered(&mut self, body: Gd<Node>) { hurt(body, self.damage); }
+}
+
+/// Hangs in place until the player walks underneath, then drops and resets.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct FallingObject {
+    base: Base<Area2D>,
+    #[export]
+    damage: u32,
+    /// How close horizontally the player has to be to trigger the fall.
+    #[export]
+    trigger_width: f32,
+    #[export]
+    gravity: f32,
+    /// Seconds after dropping before it returns to its starting point.
+    #[export]
+    reset_time: f32,
+    start: Vector2,
+    velocity: f32,
+    falling_for: Option<f32>,
+}
+
+#[godot_api]
+impl IArea2D for FallingObject {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            damage: 1,
+            trigger_width: 32.,
+            gravity: 1000.,
+            reset_time: 3.,
+            start: Vector2::ZERO,
+            velocity: 0.,
+            falling_for: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.start = self.base().get_position();
+        connect_hurtbox(self.to_gd());
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        hurt_overlapping(self.base().clone(), self.damage);
+        let delta = delta as f32;
+        let Some(falling_for) = self.falling_for else {
+            if self.player_below() {
+                self.falling_for = Some(0.);
+            }
+            return;
+        };
+
+        if falling_for >= self.reset_time {
+            self.falling_for = None;
+            self.velocity = 0.;
+            let start = self.start;
+            self.base_mut().set_position(start);
+            return;
+        }
+
+        self.falling_for = Some(falling_for + delta);
+        self.velocity += self.gravity * delta;
+        let offset = Vector2::new(0., self.velocity * delta);
+        self.base_mut().translate(offset);
+    }
+}
+
+#[godot_api]
+impl FallingObject {
+    #[func]
+    fn body_entered(&mut self, body: Gd<Node>) { hurt(body, self.damage); }
+
+    fn player_below(&self) -> bool {
+        let Some(mut tree) = self.base().get_tree() else {
+            return false;
+        };
+        let Some(player) = tree.get_first_node_in_group("player".into()) else {
+            return false;
+        };
+        let Ok(player) = player.try_cast::<Node2D>() else {
+            return false;
+        };
+        let own = self.base().get_global_position();
+        let theirs = player.get_global_position();
+        theirs.y > own.y && (theirs.x - own.x).abs() <= self.trigger_width
+    }
+}
+
+/// Patrols left and right of where it was placed, hurting the player on
+/// contact.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct AuditDrone {
+    base: Base<Area2D>,
+    #[export]
+    damage: u32,
+    #[export]
+    speed: f32,
+    /// How far either side of the start the drone travels.
+    #[export]
+    patrol_distance: f32,
+    start: Vector2,
+    direction: f32,
+}
+
+#[godot_api]
+impl IArea2D for AuditDrone {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            damage: 1,
+            speed: 80.,
+            patrol_distance: 120.,
+            start: Vector2::ZERO,
+            direction: 1.,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.start = self.base().get_position();
+        connect_hurtbox(self.to_gd());
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        hurt_overlapping(self.base().clone(), self.damage);
+        let mut position = self.base().get_position();
+        position.x += self.speed * self.direction * delta as f32;
+
+        let offset = position.x - self.start.x;
+        if offset.abs() >= self.patrol_distance {
+            position.x = self.start.x + self.patrol_distance.copysign(offset);
+            self.direction = -self.direction;
+            let scale = self.base().get_scale();
+            self.base_mut().set_scale(Vector2::new(
+                scale.x.abs() * self.direction,
+                scale.y,
+            ));
+        }
+        self.base_mut().set_position(position);
+    }
+}
+
+#[godot_api]
+impl AuditDrone {
+    #[func]
+    fn body_entered(&mut self, body: Gd<Node>) { hurt(body, self.damage); }
+}
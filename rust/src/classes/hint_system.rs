@@ -0,0 +1,160 @@
+use godot::classes::{INode2D, InputEvent, Label, Node2D};
+use godot::prelude::*;
+
+use super::graphs::bar::BarGraph;
+use super::level_state::LevelState;
+use super::minigame::MiniGame;
+use super::speedrun::Speedrun;
+
+/// Points out which bar to change next and which way, either on the `Hint`
+/// action or after the player has been idle for `idle_time`. Hints asked
+/// for count against the level's score, while idle nudges are free. Hints
+/// are off entirely during a speedrun.
+///
+/// Targets come from the level's `Boss`, so levels without one have nothing
+/// to hint at.
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct HintSystem {
+    base: Base<Node2D>,
+    /// Seconds without input before a hint is offered. 0 only gives hints
+    /// on request.
+    #[export]
+    idle_time: f32,
+    idle: f32,
+    showing: bool,
+    disabled: bool,
+    label: Option<Gd<Label>>,
+}
+
+#[godot_api]
+impl INode2D for HintSystem {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            idle_time: 20.,
+            idle: 0.,
+            showing: false,
+            disabled: false,
+            label: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.disabled = Speedrun::is_running(self.base().clone().upcast());
+
+        let mut label = Label::new_alloc();
+        label.set_as_top_level(true);
+        label.set_modulate(Color::from_rgb(1., 0.85, 0.));
+        label.hide();
+        self.base_mut().add_child(label.clone());
+        self.label = Some(label);
+
+        if let Some(mut bar_graph) =
+            BarGraph::find(self.base().clone().upcast())
+        {
+            bar_graph.connect(
+                "bar_changed".into(),
+                Callable::from_object_method(&self.to_gd(), "bar_changed"),
+            );
+        }
+    }
+
+    fn process(&mut self, delta: f64) {
+        if self.disabled || self.showing || self.idle_time <= 0. {
+            return;
+        }
+        self.idle += delta as f32;
+        if self.idle >= self.idle_time {
+            self.show_hint();
+        }
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        if event.is_action_pressed("Hint".into()) {
+            self.request_hint();
+        } else if event.is_pressed() {
+            self.idle = 0.;
+        }
+    }
+}
+
+#[godot_api]
+impl HintSystem {
+    /// Shows a hint the player asked for, which costs them points.
+    #[func]
+    fn request_hint(&mut self) {
+        if !self.show_hint() {
+            return;
+        }
+        if let Some(mut level_state) =
+            LevelState::find(self.base().clone().upcast())
+        {
+            level_state.bind_mut().record_hint();
+        }
+    }
+
+    #[func]
+    fn bar_changed(&mut self, _index: u32, _value: f32) {
+        self.idle = 0.;
+        self.showing = false;
+        if let Some(ref mut label) = self.label {
+            label.hide();
+        }
+    }
+
+    /// Shows the next move, returning whether there was one to show.
+    fn show_hint(&mut self) -> bool {
+        self.idle = 0.;
+        if self.disabled || self.showing {
+            return false;
+        }
+        let Some(mut tree) = self.base().get_tree() else {
+            return false;
+        };
+        let Some(minigame) = tree
+            .get_first_node_in_group("minigame".into())
+            .and_then(|minigame| minigame.try_cast::<MiniGame>().ok())
+        else {
+            return false;
+        };
+        let hint = minigame.bind().hint();
+        let (Some(controller), Some(bar), Some(up), Some(clicks)) = (
+            hint.get("controller"),
+            hint.get("bar"),
+            hint.get("up"),
+            hint.get("clicks"),
+        ) else {
+            return false;
+        };
+        let (controller, bar): (i32, i32) = (controller.to(), bar.to());
+        let (up, clicks): (bool, u32) = (up.to(), clicks.to());
+
+        let mut minigame = minigame;
+        minigame.bind_mut().select(controller);
+
+        let Some(bar_graph) = BarGraph::find(self.base().clone().upcast())
+        else {
+            return false;
+        };
+        let Some(bar) = bar_graph.bind().bars().into_iter().nth(bar as usize)
+        else {
+            return false;
+        };
+        let Some(ref mut label) = self.label else {
+            return false;
+        };
+        let text = if up {
+            format!("▲ +{}", clicks)
+        } else {
+            format!("▼ -{}", clicks)
+        };
+        label.set_text(text.into());
+        label.set_global_position(
+            bar.get_global_position() + Vector2::new(-12., 24.),
+        );
+        label.show();
+        self.showing = true;
+        true
+    }
+}
@@ -0,0 +1,76 @@
+use godot::classes::{Area2D, IArea2D};
+use godot::prelude::*;
+
+/// Group every interactable area joins so the player can find it.
+pub const GROUP: &str = "interactable";
+
+/// Something the player can interact with by standing in it and pressing
+/// `Interact`.
+///
+/// Godot can't dispatch on Rust traits, so implementors also expose the trait
+/// as `get_prompt` and `interact` funcs and join [`GROUP`] in `ready`. The
+/// player only ever goes through [`prompt`] and [`interact`].
+pub trait Interactable {
+    /// Short text shown above the player, as a translation key such as
+    /// `PROMPT_ENTER`.
+    fn prompt(&self) -> GString;
+
+    fn interact(&mut self, player: Gd<Node>);
+}
+
+pub fn is_interactable(node: &Gd<Area2D>) -> bool {
+    node.is_in_group(GROUP.into()) && node.has_method("interact".into())
+}
+
+pub fn prompt(node: &mut Gd<Area2D>) -> GString {
+    node.call("get_prompt".into(), &[]).to()
+}
+
+pub fn interact(node: &mut Gd<Area2D>, player: Gd<Node>) {
+    node.call("interact".into(), &[player.to_variant()]);
+}
+
+/// Generic interactable for terminals, minigame launchers and the like.
+/// Level scenes hook up `interacted` to decide what happens.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct Terminal {
+    base: Base<Area2D>,
+    #[export]
+    prompt: GString,
+}
+
+#[godot_api]
+impl IArea2D for Terminal {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            prompt: "PROMPT_USE".into(),
+        }
+    }
+
+    fn ready(&mut self) { self.base_mut().add_to_group(GROUP.into()); }
+}
+
+impl Interactable for Terminal {
+    fn prompt(&self) -> GString { self.prompt.clone() }
+
+    fn interact(&mut self, player: Gd<Node>) {
+        self.base_mut()
+            .emit_signal("interacted".into(), &[player.to_variant()]);
+    }
+}
+
+#[godot_api]
+impl Terminal {
+    #[signal]
+    fn interacted(player: Gd<Node>);
+
+    #[func]
+    fn get_prompt(&self) -> GString { Interactable::prompt(self) }
+
+    #[func]
+    fn interact(&mut self, player: Gd<Node>) {
+        Interactable::interact(self, player);
+    }
+}
@@ -0,0 +1,300 @@
+use godot::classes::{
+    Camera2D, CollisionShape2D, ColorRect, INode2D, Label, Node2D, PackedScene,
+    RandomNumberGenerator, RectangleShape2D, ResourceLoader, StaticBody2D,
+};
+use godot::prelude::*;
+
+use super::boss::Boss;
+use super::graphs::bar::BarGraph;
+use super::graphs::unit::ValueUnit;
+use super::hint_system::HintSystem;
+use super::level_state::LevelState;
+use super::locale::tr_args;
+use super::minigame::MiniGame;
+use super::player::Player;
+use super::scene_manager::ENDLESS_LEVEL;
+use super::score::LevelScoring;
+
+mod plan;
+
+use plan::{
+    plan_platforms, plan_puzzle, Jump, Platform, PuzzlePlan, SeededRng,
+};
+
+const PLAYER: &str = "res://scenes/player.tscn";
+const DOOR: &str = "res://scenes/door.tscn";
+const GRAPH: &str = "res://scenes/graph.tscn";
+const KILL_PLANE: &str = "res://scenes/killplane.tscn";
+const MINIGAME: &str = "res://scenes/minigames/signing.tscn";
+const BAR_CONTROLLER: &str = "res://scenes/minigames/bar_controller.tscn";
+
+const PLATFORM_HEIGHT: f32 = 16.;
+
+/// Builds a whole level from a seed when it enters the tree: platforms from
+/// the spawn to the exit `Door`, a `BarGraph` with controllers, a budget and
+/// boss targets that the puzzle solver has checked can be met, on platforms
+/// the player can jump between.
+///
+/// Meant as the only node of a level scene such as
+/// [`ENDLESS_LEVEL`], whose door leads back to itself with a new seed.
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+struct LevelGenerator {
+    base: Base<Node2D>,
+    /// Seed to build from. 0 picks one at random, unless the level is being
+    /// restarted, in which case the previous seed is reused.
+    #[export]
+    seed: u64,
+    /// Only picks seeds. Levels are rolled from a [`SeededRng`].
+    rng: Gd<RandomNumberGenerator>,
+}
+
+#[godot_api]
+impl INode2D for LevelGenerator {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            seed: 0,
+            rng: RandomNumberGenerator::new_gd(),
+        }
+    }
+
+    fn ready(&mut self) {
+        // The level's nodes are added as siblings, which can't happen while
+        // the parent is still readying its children.
+        self.base_mut().call_deferred("generate".into(), &[]);
+    }
+}
+
+#[godot_api]
+impl LevelGenerator {
+    #[func]
+    fn generate(&mut self) {
+        let level_state = LevelState::find(self.base().clone().upcast());
+        let seed = if self.seed != 0 {
+            self.seed
+        } else if let Some(seed) = level_state
+            .as_ref()
+            .and_then(|level_state| level_state.bind().level_seed())
+        {
+            seed
+        } else {
+            self.rng.randomize();
+            self.rng.randi() as u64
+        };
+        if let Some(mut level_state) = level_state {
+            level_state.bind_mut().set_level_seed(seed);
+        }
+        let mut rng = SeededRng::new(seed);
+
+        let Some(mut root) = self.base().get_parent() else {
+            godot_error!("LevelGenerator has no parent");
+            return;
+        };
+        let Some(player) = instantiate(PLAYER) else {
+            return;
+        };
+        let Ok(jump) = player
+            .clone()
+            .try_cast::<Player>()
+            .map(|player| Jump::new(&player.bind().movement_params()))
+        else {
+            godot_error!("{} is not a Player", PLAYER);
+            player.free();
+            return;
+        };
+        let mut boss = Boss::new_alloc();
+        let tolerance = boss.bind().get_target_tolerance();
+        let Some(plan) = plan_puzzle(&mut rng, tolerance) else {
+            godot_error!("Could not generate a solvable level from {}", seed);
+            boss.free();
+            player.free();
+            return;
+        };
+        let Some(platforms) = plan_platforms(&mut rng, &jump) else {
+            godot_error!("Could not lay out platforms for {}", seed);
+            boss.free();
+            player.free();
+            return;
+        };
+
+        let mut camera = Camera2D::new_alloc();
+        camera.set_name("Camera2D".into());
+        root.add_child(camera);
+
+        let door_position = self.build_platforms(&mut root, &platforms);
+        self.build_graph(&mut root, &plan);
+        let minigame = self.build_minigame(&mut root, &plan);
+        self.build_boss(&mut root, boss, &plan);
+        // Check the nodes as built, with the limits and targets they ended
+        // up with, rather than trusting the plan.
+        if let Some(Err(err)) =
+            minigame.map(|minigame| minigame.bind().puzzle().solve())
+        {
+            godot_error!("Level {} was built unsolvable: {}", seed, err);
+        }
+
+        let Some(mut door) = instantiate(DOOR) else {
+            return;
+        };
+        door.set("dest_path".into(), ENDLESS_LEVEL.to_variant());
+        if let Ok(mut door) = door.clone().try_cast::<Node2D>() {
+            door.set_position(door_position);
+        }
+        root.add_child(door);
+
+        if let Ok(mut player) = player.clone().try_cast::<Node2D>() {
+            player.set_position(Vector2::new(-480., -110.));
+        }
+        let Some(mut kill_plane) = instantiate(KILL_PLANE) else {
+            return;
+        };
+        kill_plane.connect(
+            "body_entered".into(),
+            Callable::from_object_method(&player, "kill"),
+        );
+        root.add_child(kill_plane);
+        root.add_child(player);
+
+        let mut scoring = LevelScoring::new_alloc();
+        scoring.set("par_time".into(), 45.0.to_variant());
+        root.add_child(scoring);
+        root.add_child(HintSystem::new_alloc());
+
+        let mut label = Label::new_alloc();
+        label.set_text(tr_args("ENDLESS_REPORT", &[&seed]).into());
+        label.set_position(Vector2::new(-560., -310.));
+        root.add_child(label);
+    }
+
+    /// Adds the planned platforms, returning where the door goes on the
+    /// last one.
+    fn build_platforms(
+        &mut self,
+        root: &mut Gd<Node>,
+        platforms: &[Platform],
+    ) -> Vector2 {
+        for planned in platforms {
+            let center =
+                Vector2::new(planned.left + planned.width / 2., planned.y);
+            root.add_child(platform(center, planned.width));
+        }
+        platforms.last().map_or(Vector2::ZERO, |last| {
+            Vector2::new(last.left + last.width / 2., last.y - 40.)
+        })
+    }
+
+    fn build_graph(&mut self, root: &mut Gd<Node>, plan: &PuzzlePlan) {
+        let Some(mut graph) = instantiate(GRAPH) else {
+            return;
+        };
+        graph.set_name("Graph".into());
+        if let Ok(mut graph) = graph.clone().try_cast::<Node2D>() {
+            graph.set_position(Vector2::new(-51., 257.));
+        }
+        let mut bar_graph = BarGraph::new_alloc();
+        bar_graph.set_name("BarGraph".into());
+        bar_graph.set("spacing".into(), 10.0.to_variant());
+        // Quarterly revenue, in hundreds of thousands.
+        bar_graph.set("unit".into(), ValueUnit::Currency.to_variant());
+        bar_graph.set("value_scale".into(), 100_000.0.to_variant());
+        bar_graph.set("zoomable".into(), true.to_variant());
+        for (idx, bar) in plan.puzzle.bars.iter().enumerate() {
+            let name = format!("Q{}", idx + 1);
+            // Bars add their own scale of 1 to the height they are given.
+            bar_graph
+                .bind_mut()
+                .add_bar_details(&name, bar.start as f32 - 1.);
+        }
+        graph.add_child(bar_graph);
+        root.add_child(graph);
+    }
+
+    fn build_minigame(
+        &mut self,
+        root: &mut Gd<Node>,
+        plan: &PuzzlePlan,
+    ) -> Option<Gd<MiniGame>> {
+        let mut minigame = instantiate(MINIGAME)?;
+        minigame.set_name("MiniGame".into());
+        minigame.set("limited".into(), true.to_variant());
+        minigame.set(
+            "available".into(),
+            plan.puzzle.budget.unwrap_or(0).to_variant(),
+        );
+        if let Ok(mut minigame) = minigame.clone().try_cast::<Node2D>() {
+            minigame.set_position(Vector2::new(458., -213.));
+        }
+        for (idx, bar) in plan.puzzle.bars.iter().enumerate() {
+            let Some(mut controller) = instantiate(BAR_CONTROLLER) else {
+                minigame.free();
+                return None;
+            };
+            controller.set("bar_index".into(), (idx as i32).to_variant());
+            controller.set("count".into(), bar.start.to_variant());
+            controller.set("minimum".into(), bar.minimum.to_variant());
+            controller.set("maximum".into(), bar.maximum.to_variant());
+            if let Ok(mut controller) = controller.clone().try_cast::<Node2D>()
+            {
+                let step = idx as f32;
+                controller.set_position(Vector2::new(
+                    84. - 18. * step,
+                    49. - 30. * step,
+                ));
+                controller.set_rotation(-0.45);
+            }
+            minigame.add_child(controller);
+        }
+        root.add_child(minigame.clone());
+        minigame.try_cast::<MiniGame>().ok()
+    }
+
+    fn build_boss(
+        &mut self,
+        root: &mut Gd<Node>,
+        mut boss: Gd<Boss>,
+        plan: &PuzzlePlan,
+    ) {
+        boss.set_name("Boss".into());
+        let targets: Vec<f32> =
+            plan.targets.iter().map(|target| *target as f32).collect();
+        boss.bind_mut()
+            .set_targets(PackedFloat32Array::from(targets.as_slice()));
+        root.add_child(boss);
+    }
+}
+
+fn instantiate(path: &str) -> Option<Gd<Node>> {
+    let Some(scene) = ResourceLoader::singleton().load(path.into()) else {
+        godot_error!("Failed to load {}", path);
+        return None;
+    };
+    let Ok(scene) = scene.try_cast::<PackedScene>() else {
+        godot_error!("Failed to cast {} as a PackedScene", path);
+        return None;
+    };
+    let node = scene.instantiate();
+    if node.is_none() {
+        godot_error!("Failed to instantiate {}", path);
+    }
+    node
+}
+
+fn platform(center: Vector2, width: f32) -> Gd<StaticBody2D> {
+    let size = Vector2::new(width, PLATFORM_HEIGHT);
+    let mut body = StaticBody2D::new_alloc();
+    body.set_position(center);
+
+    let mut shape = RectangleShape2D::new_gd();
+    shape.set_size(size);
+    let mut collision = CollisionShape2D::new_alloc();
+    collision.set_shape(shape);
+    body.add_child(collision);
+
+    let mut rect = ColorRect::new_alloc();
+    rect.set_size(size);
+    rect.set_position(-size / 2.);
+    rect.set_color(Color::from_rgb(0.15, 0.15, 0.2));
+    body.add_child(rect);
+    body
+}
@@ -0,0 +1,317 @@
+use crate::classes::player::MovementParams;
+use crate::puzzle::{target_range, BarPuzzle, PuzzleBar};
+
+/// Gives up on a seed's puzzle, or on one of its platforms, after this many
+/// failed attempts.
+const MAX_ATTEMPTS: u32 = 100;
+const MAX_BAR_VALUE: u32 = 10;
+
+/// Where the first platform starts and how far right the last one must reach.
+const START_LEFT: f32 = -540.;
+const EXIT_RIGHT: f32 = 420.;
+const MIN_GAP: f32 = 40.;
+const MAX_GAP: f32 = 90.;
+/// Most a platform moves up or down from the one before it.
+const MAX_STEP: f32 = 30.;
+const HIGHEST: f32 = -180.;
+const LOWEST: f32 = -40.;
+/// Share of the player's jump a gap may ask for, leaving room for a late
+/// take-off or a jump that isn't held.
+const JUMP_MARGIN: f32 = 0.75;
+
+/// Small deterministic generator, so a seed builds the same level everywhere
+/// and levels can be generated without the engine.
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self { Self(seed) }
+
+    // SplitMix64.
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Any whole number from `low` to `high`, inclusive.
+    pub fn range(&mut self, low: u32, high: u32) -> u32 {
+        let span = u64::from(high - low) + 1;
+        low + (self.next_u64() % span) as u32
+    }
+
+    /// Any number from `low` up to `high`.
+    pub fn range_f32(&mut self, low: f32, high: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        low + (high - low) * unit
+    }
+}
+
+/// A bar puzzle before it is turned into nodes.
+pub struct PuzzlePlan {
+    pub puzzle: BarPuzzle,
+    pub targets: Vec<u32>,
+}
+
+/// Rolls bars until every one can be brought within `tolerance` of its
+/// target inside its controller's limits, then gives a budget of the fewest
+/// clicks that takes plus a little slack.
+pub fn plan_puzzle(rng: &mut SeededRng, tolerance: f32) -> Option<PuzzlePlan> {
+    for _ in 0..MAX_ATTEMPTS {
+        let count = rng.range(3, 6) as usize;
+        let mut bars = Vec::with_capacity(count);
+        let mut targets = Vec::with_capacity(count);
+        for _ in 0..count {
+            let start = rng.range(1, 6);
+            let minimum = rng.range(1, start);
+            let maximum = rng.range(start + 1, MAX_BAR_VALUE);
+            let target = rng.range(1, MAX_BAR_VALUE);
+            let (target_min, target_max) =
+                target_range(target as f32, tolerance);
+            bars.push(PuzzleBar {
+                start,
+                minimum,
+                maximum,
+                target_min,
+                target_max,
+            });
+            targets.push(target);
+        }
+        let mut puzzle = BarPuzzle { bars, budget: None };
+        // Targets can land outside a controller's limits.
+        let Some(clicks) = puzzle.min_clicks() else {
+            continue;
+        };
+        puzzle.budget = Some(clicks + rng.range(0, 3));
+        return Some(PuzzlePlan { puzzle, targets });
+    }
+    None
+}
+
+/// How far the player gets in one jump at full running speed.
+#[derive(Clone, Copy, Debug)]
+pub struct Jump {
+    speed: f32,
+    run_speed: f32,
+    rise_gravity: f32,
+    fall_gravity: f32,
+}
+
+impl Jump {
+    pub fn new(params: &MovementParams) -> Self {
+        Self {
+            speed: params.jump_speed.abs(),
+            run_speed: params.move_speed,
+            rise_gravity: params.physics.gravity * params.rise_gravity_scale,
+            fall_gravity: params.physics.gravity * params.fall_gravity_scale,
+        }
+    }
+
+    /// How high the top of the jump is above the take-off point.
+    pub fn height(&self) -> f32 {
+        self.speed * self.speed / (2. * self.rise_gravity)
+    }
+
+    /// How far across the player is when they come back down to `rise`
+    /// above the take-off point, or `None` if that is out of reach.
+    pub fn reach(&self, rise: f32) -> Option<f32> {
+        let drop = self.height() - rise;
+        if drop < 0. {
+            return None;
+        }
+        let time = self.speed / self.rise_gravity
+            + (2. * drop / self.fall_gravity).sqrt();
+        Some(self.run_speed * time)
+    }
+}
+
+/// A platform's left edge, width and the height of its centre.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Platform {
+    pub left: f32,
+    pub width: f32,
+    pub y: f32,
+}
+
+impl Platform {
+    pub fn right(&self) -> f32 { self.left + self.width }
+
+    /// Whether `jump` gets the player from the right edge of this platform
+    /// onto `next`, within the margin.
+    pub fn can_jump_to(&self, next: &Platform, jump: &Jump) -> bool {
+        // Up is negative y.
+        let rise = self.y - next.y;
+        let gap = next.left - self.right();
+        rise <= jump.height() * JUMP_MARGIN
+            && jump
+                .reach(rise)
+                .is_some_and(|reach| gap <= reach * JUMP_MARGIN)
+    }
+}
+
+/// Lays out a run of platforms from the spawn towards the right of the
+/// screen, rolling each one again until the player can jump to it from the
+/// one before. `None` if `jump` is too weak to get anywhere.
+pub fn plan_platforms(
+    rng: &mut SeededRng,
+    jump: &Jump,
+) -> Option<Vec<Platform>> {
+    let mut platforms = vec![Platform {
+        left: START_LEFT,
+        width: 120.,
+        y: -80.,
+    }];
+    let mut last = platforms[0];
+    while last.right() < EXIT_RIGHT {
+        last = (0..MAX_ATTEMPTS)
+            .map(|_| Platform {
+                left: last.right() + rng.range_f32(MIN_GAP, MAX_GAP),
+                width: rng.range_f32(80., 160.),
+                y: (last.y + rng.range_f32(-MAX_STEP, MAX_STEP))
+                    .clamp(HIGHEST, LOWEST),
+            })
+            .find(|next| last.can_jump_to(next, jump))?;
+        platforms.push(last);
+    }
+    Some(platforms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::physics_profile::PhysicsValues;
+
+    /// The `Boss` default.
+    const TOLERANCE: f32 = 0.5;
+    const SEEDS: u64 = 500;
+
+    /// The player's defaults.
+    fn jump() -> Jump {
+        Jump {
+            speed: 300.,
+            run_speed: 250.,
+            rise_gravity: PhysicsValues::default().gravity,
+            fall_gravity: PhysicsValues::default().gravity * 1.5,
+        }
+    }
+
+    /// Plays `plan`'s solution the way the controllers would, returning
+    /// whether it leaves every bar on target within the budget.
+    fn plays_out(plan: &PuzzlePlan) -> bool {
+        let Ok(solution) = plan.puzzle.solve() else {
+            return false;
+        };
+        let mut counts: Vec<u32> =
+            plan.puzzle.bars.iter().map(|bar| bar.start).collect();
+        let mut spent = 0;
+        for step in &solution.moves {
+            let bar = plan.puzzle.bars[step.bar];
+            let count = &mut counts[step.bar];
+            if step.snap {
+                *count = (*count).clamp(bar.minimum, bar.maximum);
+            }
+            for _ in 0..step.clicks {
+                if step.up && *count < bar.maximum {
+                    *count += 1;
+                } else if !step.up && *count > bar.minimum {
+                    *count -= 1;
+                } else {
+                    return false;
+                }
+                spent += 1;
+            }
+        }
+        let on_target =
+            plan.puzzle.bars.iter().zip(&counts).all(|(bar, count)| {
+                (bar.target_min..=bar.target_max).contains(count)
+            });
+        on_target && plan.puzzle.budget.is_some_and(|budget| spent <= budget)
+    }
+
+    #[test]
+    fn seeds_give_the_same_numbers() {
+        let mut a = SeededRng::new(7);
+        let mut b = SeededRng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.range(1, 10), b.range(1, 10));
+        }
+    }
+
+    #[test]
+    fn ranges_stay_within_bounds() {
+        let mut rng = SeededRng::new(1);
+        for _ in 0..1000 {
+            assert!((3..=6).contains(&rng.range(3, 6)));
+            let value = rng.range_f32(-30., 30.);
+            assert!((-30. ..30.).contains(&value));
+        }
+        assert_eq!(rng.range(4, 4), 4);
+    }
+
+    #[test]
+    fn jumps_reach_less_far_the_higher_they_land() {
+        let jump = jump();
+        assert_eq!(jump.height(), 45.);
+        let level = jump.reach(0.).unwrap();
+        assert!((level - 136.2).abs() < 0.1);
+        assert!(jump.reach(30.).unwrap() < level);
+        assert_eq!(jump.reach(46.), None);
+    }
+
+    #[test]
+    fn platforms_out_of_reach_are_rejected() {
+        let jump = jump();
+        let from = Platform {
+            left: 0.,
+            width: 100.,
+            y: -80.,
+        };
+        let near = Platform { left: 180., ..from };
+        let far = Platform { left: 240., ..from };
+        let high = Platform { y: -130., ..near };
+        assert!(from.can_jump_to(&near, &jump));
+        assert!(!from.can_jump_to(&far, &jump));
+        assert!(!from.can_jump_to(&high, &jump));
+    }
+
+    #[test]
+    fn jumps_too_weak_for_any_gap_give_no_platforms() {
+        let jump = Jump {
+            speed: 100.,
+            ..jump()
+        };
+        assert_eq!(plan_platforms(&mut SeededRng::new(1), &jump), None);
+    }
+
+    #[test]
+    fn generated_levels_can_be_solved() {
+        for seed in 0..SEEDS {
+            let mut rng = SeededRng::new(seed);
+            let plan = plan_puzzle(&mut rng, TOLERANCE)
+                .unwrap_or_else(|| panic!("seed {} has no puzzle", seed));
+            assert!(plays_out(&plan), "seed {} can't be solved", seed);
+        }
+    }
+
+    #[test]
+    fn generated_levels_can_be_crossed() {
+        let jump = jump();
+        for seed in 0..SEEDS {
+            let mut rng = SeededRng::new(seed);
+            let platforms = plan_platforms(&mut rng, &jump)
+                .unwrap_or_else(|| panic!("seed {} has no platforms", seed));
+            for pair in platforms.windows(2) {
+                assert!(
+                    pair[0].can_jump_to(&pair[1], &jump),
+                    "seed {} has a jump from {:?} to {:?} out of reach",
+                    seed,
+                    pair[0],
+                    pair[1]
+                );
+            }
+            let last = platforms.last().unwrap();
+            assert!(last.right() >= EXIT_RIGHT, "seed {} stops short", seed);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use godot::classes::{INode, Node};
+use godot::prelude::*;
+
+use super::scene_manager::LEVELS_DIR;
+
+/// Autoload holding the state of the attempt at the current level: the
+/// checkpoint reached, deaths, hints and time spent, and the seed a
+/// generated level was built from.
+///
+/// Registered in `project.godot` as `LevelState`. `SceneManager` resets it
+/// whenever a different level is entered, while reloads to respawn keep it.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct LevelState {
+    base: Base<Node>,
+    /// Index and respawn position of the furthest checkpoint reached.
+    checkpoint: Option<(u32, Vector2)>,
+    deaths: u32,
+    elapsed: f64,
+    hints: u32,
+    level_seed: Option<u64>,
+}
+
+#[godot_api]
+impl INode for LevelState {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            checkpoint: None,
+            deaths: 0,
+            elapsed: 0.,
+            hints: 0,
+            level_seed: None,
+        }
+    }
+
+    // Not processed while the tree is paused, so pauses don't count.
+    fn process(&mut self, delta: f64) {
+        let in_level = self
+            .base()
+            .get_tree()
+            .and_then(|tree| tree.get_current_scene())
+            .is_some_and(|scene| {
+                scene
+                    .get_scene_file_path()
+                    .to_string()
+                    .starts_with(LEVELS_DIR)
+            });
+        if in_level {
+            self.elapsed += delta;
+        }
+    }
+}
+
+#[godot_api]
+impl LevelState {
+    /// Looks up the `LevelState` autoload from any node in the tree.
+    pub fn find(node: Gd<Node>) -> Option<Gd<Self>> {
+        let Some(tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return None;
+        };
+        let Some(root) = tree.get_root() else {
+            godot_error!("Could not get root");
+            return None;
+        };
+        let level_state = root.try_get_node_as::<Self>("LevelState");
+        if level_state.is_none() {
+            godot_error!("LevelState autoload is missing!");
+        }
+        level_state
+    }
+
+    /// Where the player should respawn in the current level, if they have
+    /// reached a checkpoint.
+    pub fn checkpoint(&self) -> Option<Vector2> {
+        self.checkpoint.map(|(_, position)| position)
+    }
+
+    /// Index of the furthest checkpoint reached, if any.
+    pub fn checkpoint_index(&self) -> Option<u32> {
+        self.checkpoint.map(|(index, _)| index)
+    }
+
+    /// Moves the respawn point to checkpoint `index`, unless one further
+    /// along has already been reached. Returns whether it moved.
+    #[func]
+    pub fn set_checkpoint(&mut self, index: u32, position: Vector2) -> bool {
+        if self
+            .checkpoint_index()
+            .is_some_and(|reached| reached >= index)
+        {
+            return false;
+        }
+        self.checkpoint = Some((index, position));
+        true
+    }
+
+    /// Seed the current generated level was built from. Survives restarts
+    /// so they replay the same level, but entering any scene, even the same
+    /// one through a door, clears it.
+    pub fn level_seed(&self) -> Option<u64> { self.level_seed }
+
+    pub fn set_level_seed(&mut self, seed: u64) {
+        self.level_seed = Some(seed);
+    }
+
+    /// Counts a death against the current level and returns the new total.
+    #[func]
+    pub fn record_death(&mut self) -> u32 {
+        self.deaths += 1;
+        self.deaths
+    }
+
+    #[func]
+    pub fn get_deaths(&self) -> u32 { self.deaths }
+
+    /// Counts a hint shown in the current level and returns the new total.
+    #[func]
+    pub fn record_hint(&mut self) -> u32 {
+        self.hints += 1;
+        self.hints
+    }
+
+    #[func]
+    pub fn get_hints(&self) -> u32 { self.hints }
+
+    /// Seconds spent unpaused in the current level, including respawns.
+    #[func]
+    pub fn get_elapsed(&self) -> f64 { self.elapsed }
+
+    /// Starts a fresh attempt at the current level.
+    pub fn reset(&mut self) {
+        self.checkpoint = None;
+        self.deaths = 0;
+        self.elapsed = 0.;
+        self.hints = 0;
+    }
+
+    /// Called as `SceneManager` moves on to the next scene.
+    pub fn leave(&mut self, same_scene: bool) {
+        // A generated level left for itself is rebuilt from a new seed, so
+        // it's a new level even though the path is the same.
+        if !same_scene || self.level_seed.is_some() {
+            self.reset();
+        }
+        self.level_seed = None;
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use godot::classes::TranslationServer;
+
+/// Languages offered in the settings menu, as locale code and the
+/// language's own name for itself.
+pub const LANGUAGES: [(&str, &str); 4] = [
+    ("en", "English"),
+    ("de", "Deutsch"),
+    ("fr", "Français"),
+    ("es", "Español"),
+];
+
+/// Looks `key` up in `translations/game.csv` for the current locale.
+/// Labels and buttons translate their own text, so this is only needed when
+/// building strings in code.
+pub fn tr(key: &str) -> String {
+    TranslationServer::singleton()
+        .translate(key.into())
+        .to_string()
+}
+
+/// Like [`tr`], filling the `{0}`, `{1}`... placeholders in the translation
+/// with `args`.
+pub fn tr_args(key: &str, args: &[&dyn Display]) -> String {
+    let mut text = tr(key);
+    for (idx, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", idx), &arg.to_string());
+    }
+    text
+}
+
+pub fn current_locale() -> String {
+    TranslationServer::singleton().get_locale().to_string()
+}
+
+/// Formats `value` with `decimals` places using the separators of the
+/// current locale, e.g. 1,234.5 in English and 1.234,5 in German.
+pub fn format_number(value: f64, decimals: usize) -> String {
+    format_number_in(value, decimals, &current_locale())
+}
+
+pub fn format_number_in(value: f64, decimals: usize, locale: &str) -> String {
+    let (thousands, decimal) = separators(locale);
+    let digits = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits.as_str(), None),
+    };
+
+    let mut text = String::new();
+    if value < 0. && digits.chars().any(|digit| ('1'..='9').contains(&digit)) {
+        text.push('-');
+    }
+    for (idx, digit) in whole.chars().enumerate() {
+        if idx > 0 && (whole.len() - idx) % 3 == 0 {
+            text.push_str(thousands);
+        }
+        text.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        text.push(decimal);
+        text.push_str(fraction);
+    }
+    text
+}
+
+/// Language part of `locale`, e.g. `de` for `de_AT`.
+pub fn language(locale: &str) -> &str {
+    locale.split(['_', '-']).next().unwrap_or(locale)
+}
+
+/// Thousands and decimal separators for `locale`, going by its language.
+pub fn separators(locale: &str) -> (&'static str, char) {
+    match language(locale) {
+        "de" | "es" | "it" | "nl" | "pt" => (".", ','),
+        // Narrow no-break space, so numbers don't wrap across lines.
+        "fr" => ("\u{202f}", ','),
+        _ => (",", '.'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_group_thousands_by_language() {
+        assert_eq!(format_number_in(1234567.891, 2, "en"), "1,234,567.89");
+        assert_eq!(format_number_in(1234567.891, 2, "de"), "1.234.567,89");
+        assert_eq!(
+            format_number_in(1234567.891, 2, "fr"),
+            "1\u{202f}234\u{202f}567,89"
+        );
+        assert_eq!(format_number_in(1234567.891, 2, "es_ES"), "1.234.567,89");
+        assert_eq!(format_number_in(999., 0, "en"), "999");
+    }
+
+    #[test]
+    fn rounding_can_add_a_group() {
+        assert_eq!(format_number_in(999.6, 0, "en"), "1,000");
+        assert_eq!(format_number_in(999_999.99, 1, "de"), "1.000.000,0");
+    }
+
+    #[test]
+    fn negative_numbers_keep_their_sign_unless_they_round_to_zero() {
+        assert_eq!(format_number_in(-1234.5, 1, "en"), "-1,234.5");
+        assert_eq!(format_number_in(-1234.5, 1, "fr"), "-1\u{202f}234,5");
+        assert_eq!(format_number_in(-0.004, 2, "en"), "0.00");
+    }
+
+    #[test]
+    fn locales_fall_back_to_their_language() {
+        assert_eq!(language("de_AT"), "de");
+        assert_eq!(language("pt-BR"), "pt");
+        assert_eq!(language("en"), "en");
+        assert_eq!(separators("fr_CA"), ("\u{202f}", ','));
+        assert_eq!(separators("ja"), (",", '.'));
+    }
+}
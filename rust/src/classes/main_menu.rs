@@ -1,53 +1,33 @@
-use godot::classes::{
-    Control, IControl, InputEvent, InputEventMouseButton, ResourceLoader,
-};
-use godot::global::MouseButton;
+use godot::classes::{Button, Control, IControl};
 use godot::prelude::*;
 
+use super::scene_manager::{
+    SceneManager, CREDITS, ENDLESS_LEVEL, FIRST_LEVEL, MAIN_MENU,
+};
+use super::settings::SETTINGS_MENU;
+use super::speedrun::Speedrun;
+
 #[derive(GodotClass)]
 #[class(base=Control)]
 struct MainMenu {
     base: Base<Control>,
-    first_level: Option<Gd<PackedScene>>,
-    #[export]
-    credits: Option<Gd<PackedScene>>,
 }
 
 #[godot_api]
 impl IControl for MainMenu {
-    fn init(base: Base<Control>) -> Self {
-        Self {
-            base,
-            first_level: None,
-            credits: None,
-        }
-    }
+    fn init(base: Base<Control>) -> Self { Self { base } }
 
     fn ready(&mut self) {
-        let mut resource_loader = ResourceLoader::singleton();
-        let Some(level1) =
-            resource_loader.load("res://levels/level1.tscn".into())
-        else {
-            godot_error!("Failed to load level1");
-            return;
-        };
-        let Ok(level1) = level1.try_cast::<PackedScene>() else {
-            godot_error!("Failed to cast level1 as a PackedScene");
-            return;
-        };
-        self.first_level = Some(level1);
+        // Give keyboard and controller players something to navigate from.
+        let mut start = self.base().get_node_as::<Button>("Start");
+        start.grab_focus();
 
-        let Some(credits) =
-            resource_loader.load("res://scenes/credits.tscn".into())
-        else {
-            godot_error!("Failed to load credits");
+        let Some(mut scene_manager) = self.scene_manager() else {
             return;
         };
-        let Ok(credits) = credits.try_cast::<PackedScene>() else {
-            godot_error!("Failed to cast credits as a PackedScene");
-            return;
-        };
-        self.credits = Some(credits);
+        let mut scene_manager = scene_manager.bind_mut();
+        scene_manager.preload(FIRST_LEVEL.into());
+        scene_manager.preload(CREDITS.into());
     }
 }
 
@@ -55,84 +35,81 @@ impl IControl for MainMenu {
 impl MainMenu {
     #[func]
     fn start_game(&mut self) {
-        let Some(mut tree) = self.base_mut().get_tree() else {
-            godot_error!("Could not get scene tree!");
+        let Some(mut scene_manager) = self.scene_manager() else {
             return;
         };
+        scene_manager.bind_mut().change_scene(FIRST_LEVEL.into());
+    }
 
-        let Some(ref scene) = self.first_level else {
-            godot_error!("No scene was set for this button!");
+    #[func]
+    fn endless(&mut self) {
+        let Some(mut scene_manager) = self.scene_manager() else {
             return;
         };
+        scene_manager.bind_mut().change_scene(ENDLESS_LEVEL.into());
+    }
 
-        tree.change_scene_to_packed(scene);
+    #[func]
+    fn speedrun(&mut self) {
+        let Some(mut speedrun) = Speedrun::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        speedrun.bind_mut().start();
     }
 
     #[func]
     fn credits(&mut self) {
-        let Some(mut tree) = self.base_mut().get_tree() else {
-            godot_error!("Could not get scene tree!");
+        let Some(mut scene_manager) = self.scene_manager() else {
             return;
         };
+        scene_manager.bind_mut().change_scene(CREDITS.into());
+    }
 
-        let Some(ref scene) = self.credits else {
-            godot_error!("No scene was set for this button!");
+    #[func]
+    fn settings(&mut self) {
+        let Some(mut scene_manager) = self.scene_manager() else {
             return;
         };
-
-        tree.change_scene_to_packed(scene);
+        scene_manager.bind_mut().change_scene(SETTINGS_MENU.into());
     }
 
     #[func]
-    fn exit(&mut self) { self.base().get_tree().unwrap().quit(); }
+    fn exit(&mut self) {
+        let Some(mut scene_manager) = self.scene_manager() else {
+            return;
+        };
+        scene_manager.bind_mut().quit();
+    }
+
+    fn scene_manager(&self) -> Option<Gd<SceneManager>> {
+        SceneManager::find(self.base().clone().upcast())
+    }
 }
 
 #[derive(GodotClass)]
 #[class(base=Control)]
 struct Credits {
     base: Base<Control>,
-    main_menu: Option<Gd<PackedScene>>,
 }
 
 #[godot_api]
 impl IControl for Credits {
-    fn init(base: Base<Control>) -> Self {
-        Self {
-            base,
-            main_menu: None,
-        }
-    }
-
-    fn ready(&mut self) {
-        let mut resource_loader = ResourceLoader::singleton();
-        let Some(main_menu) =
-            resource_loader.load("res://scenes/MainMenu.tscn".into())
-        else {
-            godot_error!("Failed to load credits");
-            return;
-        };
-        let Ok(main_menu) = main_menu.try_cast::<PackedScene>() else {
-            godot_error!("Failed to cast credits as a PackedScene");
-            return;
-        };
-        self.main_menu = Some(main_menu);
-    }
+    fn init(base: Base<Control>) -> Self { Self { base } }
 }
 
 #[godot_api]
 impl Credits {
     #[func]
     fn main_menu(&mut self) {
-        let Some(mut tree) = self.base_mut().get_tree() else {
-            godot_error!("Could not get scene tree!");
-            return;
-        };
-
-        let Some(ref scene) = self.main_menu else {
-            godot_error!("No scene was set for this button!");
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
             return;
         };
-
-        tree.change_scene_to_packed(scene);
+        let mut scene_manager = scene_manager.bind_mut();
+        if !scene_manager.back() {
+            scene_manager.change_scene(MAIN_MENU.into());
+        }
     }
 }
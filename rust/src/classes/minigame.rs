@@ -1,22 +1,28 @@
 use std::u32;
 
 use godot::classes::{
-    Area2D, IArea2D, INode2D, InputEvent, InputEventMouseButton, Node2D,
-    SceneTree,
+    Area2D, CollisionShape2D, IArea2D, INode2D, InputEvent,
+    InputEventMouseButton, Node2D, RectangleShape2D,
 };
 use godot::global::MouseButton;
 use godot::prelude::*;
 
+use super::boss::Boss;
 use super::graphs::bar::BarGraph;
+use crate::puzzle::{BarPuzzle, PuzzleBar};
 
 #[derive(GodotClass)]
 #[class(base=Node2D)]
-struct MiniGame {
+pub struct MiniGame {
     base: Base<Node2D>,
     #[export]
     limited: bool,
     #[export]
     available: u32,
+    /// Index of the controller picked with `BarNext`/`BarPrevious`, or -1
+    /// before the player has selected one.
+    #[var]
+    selected: i32,
 }
 
 #[godot_api]
@@ -26,10 +32,153 @@ impl INode2D for MiniGame {
             base,
             limited: false,
             available: 0,
+            selected: -1,
         }
     }
 
-    fn ready(&mut self) {}
+    fn ready(&mut self) { self.base_mut().add_to_group("minigame".into()); }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if event.is_action_pressed("BarNext".into()) {
+            self.cycle(1);
+        } else if event.is_action_pressed("BarPrevious".into()) {
+            self.cycle(-1);
+        } else if event.is_action_pressed("BarIncrease".into()) {
+            self.adjust_selected(true);
+        } else if event.is_action_pressed("BarDecrease".into()) {
+            self.adjust_selected(false);
+        } else {
+            return;
+        }
+        self.base().get_viewport().unwrap().set_input_as_handled();
+    }
+}
+
+#[godot_api]
+impl MiniGame {
+    /// Emitted whenever the player clicks or adjusts a `BarController`.
+    #[signal]
+    fn controller_clicked(bar_index: i32);
+
+    /// Suggests the next click towards the cheapest solution, as a
+    /// dictionary with the `controller` and `bar` indices, whether to go
+    /// `up` and how many `clicks` that bar needs. Empty when the minigame is
+    /// already solved or can't be.
+    #[func]
+    pub fn hint(&self) -> Dictionary {
+        let mut hint = Dictionary::new();
+        let Ok(solution) = self.puzzle().solve() else {
+            return hint;
+        };
+        let Some(step) = solution.moves.first() else {
+            return hint;
+        };
+        let Some(controller) = self.controllers().into_iter().nth(step.bar)
+        else {
+            return hint;
+        };
+        hint.set("controller", step.bar as i64);
+        hint.set("bar", controller.bind().bar_index);
+        hint.set("up", step.up);
+        hint.set("clicks", step.clicks);
+        hint
+    }
+
+    /// Fewest clicks left to solve the minigame, or -1 if it can't be.
+    #[func]
+    fn min_clicks(&self) -> i64 {
+        self.puzzle()
+            .solve()
+            .map_or(-1, |solution| solution.clicks as i64)
+    }
+
+    /// The minigame as it stands, for the solver. Targets come from the
+    /// level's `Boss`; bars it has no target for are always on target.
+    pub fn puzzle(&self) -> BarPuzzle {
+        let targets = self
+            .base()
+            .get_tree()
+            .and_then(|mut tree| tree.get_first_node_in_group("boss".into()))
+            .and_then(|boss| boss.try_cast::<Boss>().ok())
+            .map(|boss| boss.bind().target_ranges())
+            .unwrap_or_default();
+        let bars = self
+            .controllers()
+            .iter()
+            .map(|controller| {
+                let controller = controller.bind();
+                let (target_min, target_max) =
+                    usize::try_from(controller.bar_index)
+                        .ok()
+                        .and_then(|idx| targets.get(idx).copied())
+                        .unwrap_or((0, u32::MAX));
+                PuzzleBar {
+                    start: controller.count,
+                    minimum: controller.minimum,
+                    maximum: controller.maximum,
+                    target_min,
+                    target_max,
+                }
+            })
+            .collect();
+        BarPuzzle {
+            bars,
+            budget: self.limited.then_some(self.available),
+        }
+    }
+
+    fn controllers(&self) -> Vec<Gd<BarController>> {
+        self.base()
+            .get_children()
+            .iter_shared()
+            .filter_map(|child| child.try_cast::<BarController>().ok())
+            .collect()
+    }
+
+    fn cycle(&mut self, direction: i32) {
+        let count = self.controllers().len() as i32;
+        if count == 0 {
+            return;
+        }
+        let selected = if self.selected < 0 {
+            0
+        } else {
+            (self.selected + direction).rem_euclid(count)
+        };
+        self.select(selected);
+    }
+
+    /// Selects the controller at `index` for keyboard and gamepad input,
+    /// outlining it.
+    pub fn select(&mut self, index: i32) {
+        self.selected = index;
+        for (idx, mut controller) in self.controllers().into_iter().enumerate()
+        {
+            controller.bind_mut().set_selected(idx as i32 == index);
+        }
+    }
+
+    fn adjust_selected(&mut self, up: bool) {
+        if self.selected < 0 {
+            self.cycle(1);
+            return;
+        }
+        let Some(mut controller) =
+            self.controllers().into_iter().nth(self.selected as usize)
+        else {
+            return;
+        };
+        let limited = self.limited;
+        let bar_index = {
+            let mut controller = controller.bind_mut();
+            controller.adjust(up, limited, &mut self.available);
+            controller.bar_index
+        };
+        self.base_mut().emit_signal(
+            "controller_clicked".into(),
+            &[bar_index.to_variant()],
+        );
+    }
 }
 
 #[derive(GodotClass)]
@@ -44,6 +193,7 @@ struct BarController {
     minimum: u32,
     #[export]
     maximum: u32,
+    selected: bool,
 }
 
 #[godot_api]
@@ -55,12 +205,38 @@ impl IArea2D for BarController {
             count: 0,
             minimum: 0,
             maximum: u32::MAX,
+            selected: false,
         }
     }
 
     fn ready(&mut self) {}
 
     fn process(&mut self, _delta: f64) { self.set_bar_height(self.count); }
+
+    fn draw(&mut self) {
+        if !self.selected {
+            return;
+        }
+        let Some(shape) = self
+            .base()
+            .try_get_node_as::<CollisionShape2D>("CollisionShape2D")
+        else {
+            return;
+        };
+        let Some(rect) = shape
+            .get_shape()
+            .and_then(|shape| shape.try_cast::<RectangleShape2D>().ok())
+        else {
+            return;
+        };
+        let size = rect.get_size();
+        let outline = Rect2::new(shape.get_position() - size / 2., size);
+        self.base_mut()
+            .draw_rect_ex(outline, Color::from_rgb(1., 0.85, 0.))
+            .filled(false)
+            .width(2.)
+            .done();
+    }
 }
 
 #[godot_api]
@@ -80,65 +256,64 @@ impl BarController {
             godot_error!("Could not cast parent to MiniGame");
             return;
         };
-        let limited: bool = minigame.call("get_limited".into(), &[]).to();
-        let available: u32 = minigame.call("get_available".into(), &[]).to();
 
         if let Ok(mouse) = event.try_cast::<InputEventMouseButton>() {
             if mouse.is_pressed() {
-                if mouse.get_button_index() == MouseButton::LEFT {
-                    if self.count >= self.maximum {
-                        self.count = self.maximum;
-                        return;
-                    }
-                    if limited {
-                        if available > 0 {
-                            minigame.call(
-                                "set_available".into(),
-                                &[Variant::from(available - 1)],
-                            );
-                            self.count += 1;
-                        }
-                    } else {
-                        self.count += 1;
-                    }
-                } else {
-                    if self.count <= self.minimum {
-                        self.count = self.minimum;
-                        return;
-                    }
-                    if limited {
-                        minigame.call(
-                            "set_available".into(),
-                            &[Variant::from(available - 1)],
-                        );
-                        self.count -= 1;
-                    } else {
-                        self.count -= 1;
-                    }
+                let up = mouse.get_button_index() == MouseButton::LEFT;
+                {
+                    let mut minigame = minigame.bind_mut();
+                    let limited = minigame.limited;
+                    self.adjust(up, limited, &mut minigame.available);
                 }
+                minigame.emit_signal(
+                    "controller_clicked".into(),
+                    &[self.bar_index.to_variant()],
+                );
             }
         }
     }
 
+    /// Moves the count one step up or down within `minimum..=maximum`,
+    /// spending one of `available` when the minigame is limited.
+    fn adjust(&mut self, up: bool, limited: bool, available: &mut u32) {
+        if up && self.count >= self.maximum {
+            self.count = self.maximum;
+            return;
+        }
+        if !up && self.count <= self.minimum {
+            self.count = self.minimum;
+            return;
+        }
+        if limited {
+            if *available == 0 {
+                return;
+            }
+            *available -= 1;
+        }
+        if up {
+            self.count += 1;
+        } else {
+            self.count -= 1;
+        }
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+        self.base_mut().queue_redraw();
+    }
+
     #[func]
     fn set_bar_height(&mut self, height: u32) {
         if self.bar_index < 0 {
             godot_error!("No Bar was set!");
             return;
         }
-        let Some(tree) = self.base().get_tree() else {
-            godot_error!("Failed to get Tree");
+        let Some(mut bar_graph) = BarGraph::find(self.base().clone().upcast())
+        else {
+            godot_error!("Failed to find BarGraph");
             return;
         };
 
-        let Some(root) = tree.get_root() else {
-            godot_error!("Failed to get Root");
-            return;
-        };
-
-        let mut bar_graph =
-            root.get_node_as::<BarGraph>("Node2D/Graph/BarGraph");
-
         bar_graph.call(
             "set_bar_height".into(),
             &[Variant::from(self.bar_index), Variant::from(height)],
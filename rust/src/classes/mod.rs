@@ -1,5 +1,25 @@
+mod ability_pickup;
+mod boss;
+mod checkpoint;
+mod dialogue;
 mod door;
 mod graphs;
+mod hazards;
+mod hint_system;
+mod interactable;
+mod level_generator;
+mod level_state;
+mod locale;
 mod main_menu;
 mod minigame;
+mod pause_menu;
+mod physics_profile;
 mod player;
+mod replay;
+mod results;
+mod save_data;
+mod scene_manager;
+mod score;
+mod settings;
+mod speedrun;
+mod tutorial;
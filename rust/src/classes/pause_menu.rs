@@ -0,0 +1,126 @@
+use godot::classes::node::ProcessMode;
+use godot::classes::{
+    Button, Control, IControl, InputEvent, PackedScene, ResourceLoader,
+};
+use godot::prelude::*;
+
+use super::scene_manager::{SceneManager, MAIN_MENU};
+use super::settings::{SettingsMenu, SETTINGS_MENU};
+
+/// In-level pause overlay. Lives in the `PauseLayer` autoload so it survives
+/// scene changes, and only responds to `Pause` while a level is running.
+#[derive(GodotClass)]
+#[class(base=Control)]
+struct PauseMenu {
+    base: Base<Control>,
+}
+
+#[godot_api]
+impl IControl for PauseMenu {
+    fn init(base: Base<Control>) -> Self { Self { base } }
+
+    fn ready(&mut self) {
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+        self.base_mut().hide();
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if !event.is_action_pressed("Pause".into()) {
+            return;
+        }
+        // Let the settings overlay finish before closing the menu under it.
+        if !self.base().get_node_as::<Control>("Buttons").is_visible()
+            && self.base().is_visible()
+        {
+            return;
+        }
+        let Some(scene_manager) = self.scene_manager() else {
+            return;
+        };
+        if !scene_manager.bind().in_level() {
+            return;
+        }
+        let paused = !self.base().is_visible();
+        self.set_paused(paused);
+        self.base().get_viewport().unwrap().set_input_as_handled();
+    }
+}
+
+#[godot_api]
+impl PauseMenu {
+    #[func]
+    fn resume(&mut self) { self.set_paused(false); }
+
+    #[func]
+    fn restart(&mut self) {
+        self.set_paused(false);
+        let Some(mut scene_manager) = self.scene_manager() else {
+            return;
+        };
+        scene_manager.bind_mut().restart();
+    }
+
+    #[func]
+    fn settings(&mut self) {
+        let Some(scene) =
+            ResourceLoader::singleton().load(SETTINGS_MENU.into())
+        else {
+            godot_error!("Failed to load {}", SETTINGS_MENU);
+            return;
+        };
+        let Ok(scene) = scene.try_cast::<PackedScene>() else {
+            godot_error!("Failed to cast {} as a PackedScene", SETTINGS_MENU);
+            return;
+        };
+        let Some(menu) = scene.instantiate() else {
+            godot_error!("Failed to instantiate {}", SETTINGS_MENU);
+            return;
+        };
+        let Ok(mut menu) = menu.try_cast::<SettingsMenu>() else {
+            godot_error!("{} is not a SettingsMenu", SETTINGS_MENU);
+            return;
+        };
+        menu.bind_mut().set_overlay(true);
+        menu.connect(
+            "closed".into(),
+            Callable::from_object_method(&self.to_gd(), "settings_closed"),
+        );
+        self.base().get_node_as::<Control>("Buttons").hide();
+        self.base_mut().add_child(menu);
+    }
+
+    #[func]
+    fn settings_closed(&mut self) {
+        self.base().get_node_as::<Control>("Buttons").show();
+        let mut settings =
+            self.base().get_node_as::<Button>("Buttons/Settings");
+        settings.grab_focus();
+    }
+
+    #[func]
+    fn main_menu(&mut self) {
+        self.set_paused(false);
+        let Some(mut scene_manager) = self.scene_manager() else {
+            return;
+        };
+        scene_manager.bind_mut().change_scene(MAIN_MENU.into());
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        tree.set_pause(paused);
+        self.base_mut().set_visible(paused);
+        if paused {
+            let mut resume =
+                self.base().get_node_as::<Button>("Buttons/Resume");
+            resume.grab_focus();
+        }
+    }
+
+    fn scene_manager(&self) -> Option<Gd<SceneManager>> {
+        SceneManager::find(self.base().clone().upcast())
+    }
+}
@@ -0,0 +1,113 @@
+use godot::classes::{Area2D, IArea2D, IResource, Resource};
+use godot::prelude::*;
+
+use super::player::Player;
+use crate::GRAVITY;
+
+/// Plain copy of a [`PhysicsProfile`], so the player doesn't need to bind the
+/// resource every physics frame.
+#[derive(Clone, Copy, Debug)]
+pub struct PhysicsValues {
+    pub gravity: f32,
+    pub terminal_velocity: f32,
+    pub air_control: f32,
+    pub friction: f32,
+}
+
+impl Default for PhysicsValues {
+    fn default() -> Self {
+        Self {
+            gravity: GRAVITY,
+            terminal_velocity: 1500.,
+            air_control: 1.,
+            friction: 1.,
+        }
+    }
+}
+
+/// Tunable physics for a level or for an area inside one.
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct PhysicsProfile {
+    #[export]
+    gravity: f32,
+    #[export]
+    terminal_velocity: f32,
+    /// Multiplier on the player's acceleration while airborne.
+    #[export]
+    air_control: f32,
+    /// Multiplier on the player's deceleration on the ground. Low values make
+    /// floors slippery.
+    #[export]
+    friction: f32,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for PhysicsProfile {
+    fn init(base: Base<Resource>) -> Self {
+        let defaults = PhysicsValues::default();
+        Self {
+            gravity: defaults.gravity,
+            terminal_velocity: defaults.terminal_velocity,
+            air_control: defaults.air_control,
+            friction: defaults.friction,
+            base,
+        }
+    }
+}
+
+impl PhysicsProfile {
+    pub fn values(&self) -> PhysicsValues {
+        PhysicsValues {
+            gravity: self.gravity,
+            terminal_velocity: self.terminal_velocity,
+            air_control: self.air_control,
+            friction: self.friction,
+        }
+    }
+}
+
+/// Area that overrides the player's physics while they are inside it.
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+struct PhysicsZone {
+    base: Base<Area2D>,
+    #[export]
+    profile: Option<Gd<PhysicsProfile>>,
+}
+
+#[godot_api]
+impl IArea2D for PhysicsZone {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            profile: None,
+        }
+    }
+}
+
+#[godot_api]
+impl PhysicsZone {
+    #[func]
+    fn body_entered(&mut self, body: Gd<Node>) {
+        let Ok(mut player) = body.try_cast::<Player>() else {
+            return;
+        };
+        let Some(ref profile) = self.profile else {
+            godot_error!("No PhysicsProfile was set for this zone!");
+            return;
+        };
+        let zone = self.base().instance_id();
+        player.bind_mut().enter_zone(zone, profile.clone());
+    }
+
+    #[func]
+    fn body_exited(&mut self, body: Gd<Node>) {
+        let Ok(mut player) = body.try_cast::<Player>() else {
+            return;
+        };
+        let zone = self.base().instance_id();
+        player.bind_mut().exit_zone(zone);
+    }
+}
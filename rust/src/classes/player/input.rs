@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+use godot::classes::Input;
+
+/// Movement input for a single physics frame, read once so the same values
+/// can be acted on and recorded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameInput {
+    /// -1 for full left through 1 for full right.
+    pub direction: f32,
+    pub left: bool,
+    pub right: bool,
+    /// Jump was pressed this frame.
+    pub jump_pressed: bool,
+    pub jump_held: bool,
+}
+
+/// Where the player's movement input comes from each physics frame.
+pub trait InputSource {
+    fn next_frame(&mut self) -> FrameInput;
+}
+
+/// Reads the `InputMap` actions, for normal play.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LiveInput;
+
+impl InputSource for LiveInput {
+    fn next_frame(&mut self) -> FrameInput {
+        let input = Input::singleton();
+        FrameInput {
+            direction: input.get_axis("MoveLeft".into(), "MoveRight".into()),
+            left: input.is_action_pressed("MoveLeft".into()),
+            right: input.is_action_pressed("MoveRight".into()),
+            jump_pressed: input.is_action_just_pressed("Jump".into()),
+            jump_held: input.is_action_pressed("Jump".into()),
+        }
+    }
+}
+
+/// Plays back a fixed list of frames, then no input at all once they run
+/// out.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedInput {
+    frames: VecDeque<FrameInput>,
+}
+
+impl ScriptedInput {
+    pub fn new(frames: impl IntoIterator<Item = FrameInput>) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool { self.frames.is_empty() }
+}
+
+impl InputSource for ScriptedInput {
+    fn next_frame(&mut self) -> FrameInput {
+        self.frames.pop_front().unwrap_or_default()
+    }
+}
@@ -0,0 +1,492 @@
+use godot::classes::{
+    AnimationPlayer, Area2D, CharacterBody2D, ICharacterBody2D, InputEvent,
+    Label,
+};
+use godot::prelude::*;
+
+use super::interactable;
+use super::level_state::LevelState;
+use super::physics_profile::{PhysicsProfile, PhysicsValues};
+use super::scene_manager::SceneManager;
+
+mod input;
+mod movement;
+mod state;
+
+pub use input::{FrameInput, InputSource, LiveInput, ScriptedInput};
+pub use movement::MovementParams;
+use movement::{Contacts, Movement};
+use state::{PlayerState, StateInput};
+
+/// How long the player stays in `PlayerState::Interact` after interacting.
+const INTERACT_TIME: f32 = 0.25;
+
+/// Optional movement abilities, granted per level on the `Player` instance or
+/// picked up with an `AbilityPickup`.
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum Ability {
+    WallSlide,
+    WallJump,
+    LedgeGrab,
+    DoubleJump,
+}
+
+#[derive(GodotClass)]
+#[class(base=CharacterBody2D)]
+pub struct Player {
+    base: Base<CharacterBody2D>,
+    #[export]
+    jump_speed: f32,
+    #[export]
+    move_speed: f32,
+    #[export]
+    has_key: bool,
+    /// Keep the graph as the player left it when they die, instead of
+    /// reloading the level.
+    #[export]
+    keep_graph_on_death: bool,
+    #[export]
+    max_health: u32,
+    /// Seconds after being hurt during which hazards can't hurt again.
+    #[export]
+    invulnerability_time: f32,
+    #[var]
+    health: u32,
+    /// Seconds after walking off a ledge during which a jump still works.
+    #[export]
+    coyote_time: f32,
+    /// Seconds a jump press is remembered before landing.
+    #[export]
+    jump_buffer_time: f32,
+    /// Upward velocity is multiplied by this when jump is released early.
+    #[export]
+    jump_cut_multiplier: f32,
+    #[export]
+    rise_gravity_scale: f32,
+    #[export]
+    fall_gravity_scale: f32,
+    #[export]
+    acceleration: f32,
+    #[export]
+    deceleration: f32,
+    #[export]
+    air_acceleration: f32,
+    /// Physics for this level. Zones the player is standing in take
+    /// priority over it.
+    #[export]
+    physics_profile: Option<Gd<PhysicsProfile>>,
+    /// Profiles of the zones the player is in, by zone, innermost last.
+    zones: Vec<(InstanceId, Gd<PhysicsProfile>)>,
+    #[export]
+    can_wall_slide: bool,
+    #[export]
+    can_wall_jump: bool,
+    #[export]
+    can_ledge_grab: bool,
+    #[export]
+    can_double_jump: bool,
+    /// Maximum fall speed while sliding down a wall.
+    #[export]
+    wall_slide_speed: f32,
+    /// Velocity away from and up the wall when wall jumping.
+    #[export]
+    wall_jump_velocity: Vector2,
+    /// Extra jumps allowed in the air with `can_double_jump`.
+    #[export]
+    air_jumps: u32,
+    /// How far above the player's origin to look for the top of a wall.
+    #[export]
+    ledge_grab_height: f32,
+    spawn_position: Vector2,
+    movement: Movement,
+    state: PlayerState,
+    interact_timer: f32,
+    dead: bool,
+    invulnerable_timer: f32,
+    input_source: Box<dyn InputSource>,
+    input: FrameInput,
+    animation_player: Option<Gd<AnimationPlayer>>,
+    prompt_label: Option<Gd<Label>>,
+}
+
+#[godot_api]
+impl ICharacterBody2D for Player {
+    fn init(base: Base<CharacterBody2D>) -> Self {
+        Self {
+            base,
+            jump_speed: -300.,
+            move_speed: 250.,
+            has_key: false,
+            keep_graph_on_death: true,
+            max_health: 3,
+            invulnerability_time: 1.,
+            health: 3,
+            coyote_time: 0.1,
+            jump_buffer_time: 0.1,
+            jump_cut_multiplier: 0.5,
+            rise_gravity_scale: 1.,
+            fall_gravity_scale: 1.5,
+            acceleration: 2000.,
+            deceleration: 2500.,
+            air_acceleration: 1200.,
+            physics_profile: None,
+            zones: Vec::new(),
+            can_wall_slide: false,
+            can_wall_jump: false,
+            can_ledge_grab: false,
+            can_double_jump: false,
+            wall_slide_speed: 80.,
+            wall_jump_velocity: Vector2::new(250., -300.),
+            air_jumps: 1,
+            ledge_grab_height: 20.,
+            spawn_position: Vector2::ZERO,
+            movement: Movement::default(),
+            state: PlayerState::Idle,
+            interact_timer: 0.,
+            dead: false,
+            invulnerable_timer: 0.,
+            input_source: Box::new(LiveInput),
+            input: FrameInput::default(),
+            animation_player: None,
+            prompt_label: None,
+        }
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        let delta = real::from_f64(delta);
+        self.input = self.input_source.next_frame();
+        let params = self.movement_params();
+        let contacts = Contacts {
+            on_floor: self.base().is_on_floor(),
+            on_wall: self.base().is_on_wall_only(),
+            wall_normal: self.base().get_wall_normal(),
+        };
+        let velocity = self.base().get_velocity();
+        let mut velocity =
+            self.movement.fall(&params, contacts, velocity, delta);
+
+        if self.state.allows_movement() {
+            let input = self.input;
+            // Stepped on a copy so `at_ledge` can borrow the player.
+            let mut movement = self.movement;
+            velocity = movement.steer(
+                &params,
+                &input,
+                contacts,
+                velocity,
+                delta,
+                |wall_normal| self.at_ledge(wall_normal),
+            );
+            self.movement = movement;
+            self.face(&input);
+        } else {
+            velocity.x = 0.;
+        }
+        self.base_mut().set_velocity(velocity);
+
+        let _collided = self.base_mut().move_and_slide();
+
+        self.interact_timer -= delta;
+        self.invulnerable_timer -= delta;
+        self.update_state();
+        self.update_prompt();
+    }
+
+    fn ready(&mut self) {
+        self.animation_player = self
+            .base()
+            .try_get_node_as("CollisionShape2D/Sprite2D/AnimationPlayer");
+        self.spawn_position = self.base().get_global_position();
+        self.health = self.max_health;
+        self.base_mut().add_to_group("player".into());
+
+        // Top level so flipping the player to face left doesn't mirror it.
+        let mut prompt_label = Label::new_alloc();
+        prompt_label.set_as_top_level(true);
+        prompt_label.hide();
+        self.base_mut().add_child(prompt_label.clone());
+        self.prompt_label = Some(prompt_label);
+        let Some(level_state) = LevelState::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        if let Some(checkpoint) = level_state.bind().checkpoint() {
+            self.base_mut().set_global_position(checkpoint);
+        }
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        if event.is_action_pressed("Interact".into()) {
+            self.interact_timer = INTERACT_TIME;
+            if let Some(mut target) = self.nearest_interactable() {
+                let player = self.to_gd().upcast();
+                // Hold the base guard so the target can call back into the
+                // player, e.g. a locked door asking for `has_key`.
+                let _guard = self.base_mut();
+                interactable::interact(&mut target, player);
+            }
+        }
+        if event.is_action_pressed("Reset".into()) {
+            let Some(mut scene_manager) =
+                SceneManager::find(self.base().clone().upcast())
+            else {
+                return;
+            };
+            scene_manager.bind_mut().reload();
+        }
+    }
+}
+
+#[godot_api]
+impl Player {
+    #[signal]
+    fn game_over();
+
+    #[signal]
+    fn state_changed(from: GString, to: GString);
+
+    #[signal]
+    fn health_changed(health: u32);
+
+    #[signal]
+    fn ability_granted(ability: Ability);
+
+    /// The movement input the player acted on this physics frame.
+    pub fn frame_input(&self) -> FrameInput { self.input }
+
+    /// Replaces where movement input comes from, e.g. with a
+    /// [`ScriptedInput`] to drive the player from a script.
+    pub fn set_input_source(&mut self, source: Box<dyn InputSource>) {
+        self.input_source = source;
+    }
+
+    pub fn grant(&mut self, ability: Ability) {
+        match ability {
+            Ability::WallSlide => self.can_wall_slide = true,
+            Ability::WallJump => self.can_wall_jump = true,
+            Ability::LedgeGrab => self.can_ledge_grab = true,
+            Ability::DoubleJump => self.can_double_jump = true,
+        }
+        self.base_mut()
+            .emit_signal("ability_granted".into(), &[ability.to_variant()]);
+    }
+
+    #[func]
+    fn kill(player: Gd<Node>) {
+        let Ok(mut player) = player.try_cast::<Player>() else {
+            return;
+        };
+        if player.bind().dead {
+            return;
+        }
+        let Some(mut level_state) = LevelState::find(player.clone().upcast())
+        else {
+            return;
+        };
+        level_state.bind_mut().record_death();
+        player.bind_mut().dead = true;
+        player.bind_mut().update_state();
+        player.emit_signal("game_over".into(), &[]);
+
+        if player.bind().keep_graph_on_death {
+            let checkpoint = level_state.bind().checkpoint();
+            player.bind_mut().respawn(checkpoint);
+        } else if let Some(mut scene_manager) =
+            SceneManager::find(player.clone().upcast())
+        {
+            scene_manager.bind_mut().reload();
+        }
+    }
+
+    /// Takes `amount` health from the player, killing them once it runs out.
+    #[func]
+    pub fn damage(player: Gd<Node>, amount: u32) {
+        let Ok(mut player) = player.try_cast::<Player>() else {
+            return;
+        };
+        let health = {
+            let mut player = player.bind_mut();
+            if player.dead || player.invulnerable_timer > 0. {
+                return;
+            }
+            player.health = player.health.saturating_sub(amount);
+            player.invulnerable_timer = player.invulnerability_time;
+            player.health
+        };
+        player.emit_signal("health_changed".into(), &[health.to_variant()]);
+        if health == 0 {
+            Player::kill(player.upcast());
+        }
+    }
+
+    fn respawn(&mut self, checkpoint: Option<Vector2>) {
+        let position = checkpoint.unwrap_or(self.spawn_position);
+        self.base_mut().set_global_position(position);
+        self.base_mut().set_velocity(Vector2::ZERO);
+        self.dead = false;
+        self.movement.ledge_grabbing = false;
+        self.health = self.max_health;
+        let health = self.health;
+        self.base_mut()
+            .emit_signal("health_changed".into(), &[health.to_variant()]);
+        self.update_state();
+    }
+
+    pub fn enter_zone(
+        &mut self,
+        zone: InstanceId,
+        profile: Gd<PhysicsProfile>,
+    ) {
+        self.zones.push((zone, profile));
+    }
+
+    /// Leaves `zone` only, even if other zones share its profile.
+    pub fn exit_zone(&mut self, zone: InstanceId) {
+        self.zones.retain(|(id, _)| *id != zone);
+    }
+
+    /// The physics currently acting on the player: the innermost zone, then
+    /// the level profile, then the defaults.
+    fn physics(&self) -> PhysicsValues {
+        self.zones
+            .last()
+            .map(|(_, profile)| profile)
+            .or(self.physics_profile.as_ref())
+            .map(|profile| profile.bind().values())
+            .unwrap_or_default()
+    }
+
+    fn nearest_interactable(&self) -> Option<Gd<Area2D>> {
+        let collider =
+            self.base().try_get_node_as::<Area2D>("InteractCollider")?;
+        let position = self.base().get_global_position();
+        collider
+            .get_overlapping_areas()
+            .iter_shared()
+            .filter(interactable::is_interactable)
+            .min_by(|a, b| {
+                let a = a.get_global_position().distance_squared_to(position);
+                let b = b.get_global_position().distance_squared_to(position);
+                a.total_cmp(&b)
+            })
+    }
+
+    fn update_prompt(&mut self) {
+        let target = self.nearest_interactable();
+        let position = self.base().get_global_position();
+        let Some(ref mut prompt_label) = self.prompt_label else {
+            return;
+        };
+        let Some(mut target) = target else {
+            prompt_label.hide();
+            return;
+        };
+        prompt_label.set_text(interactable::prompt(&mut target));
+        prompt_label.set_global_position(position + Vector2::new(-20., -60.));
+        prompt_label.show();
+    }
+
+    fn update_state(&mut self) {
+        let velocity = self.base().get_velocity();
+        let input = StateInput {
+            on_floor: self.base().is_on_floor(),
+            velocity_x: velocity.x,
+            velocity_y: velocity.y,
+            interacting: self.interact_timer > 0.,
+            wall_sliding: self.movement.wall_sliding,
+            ledge_grabbing: self.movement.ledge_grabbing,
+            dead: self.dead,
+        };
+        let next = self.state.next(&input);
+        if next == self.state {
+            return;
+        }
+        let previous = self.state;
+        self.state = next;
+        self.play_animation(next);
+        self.base_mut().emit_signal(
+            "state_changed".into(),
+            &[previous.name().to_variant(), next.name().to_variant()],
+        );
+    }
+
+    fn play_animation(&mut self, state: PlayerState) {
+        let Some(ref mut animation_player) = self.animation_player else {
+            return;
+        };
+        let animation = if animation_player.has_animation(state.name().into()) {
+            state.name()
+        } else {
+            state.fallback_animation()
+        };
+        animation_player.play_ex().name(animation.into()).done();
+    }
+
+    #[func]
+    fn pickup_key(body: Gd<Node>) {
+        let body_clone = body.clone();
+        let Ok(mut player) = body_clone.try_cast::<Player>() else {
+            return;
+        };
+        player.call("set_has_key".into(), &[Variant::from(true)]);
+        let Some(tree) = body.get_tree() else {
+            godot_error!("Could not get tree");
+            return;
+        };
+        let Some(root) = tree.get_root() else {
+            godot_error!("Could not get root");
+            return;
+        };
+        root.get_node_as::<Area2D>("Node2D/Key").queue_free();
+    }
+
+    /// The player's movement tuning and the physics acting on them.
+    pub fn movement_params(&self) -> MovementParams {
+        MovementParams {
+            jump_speed: self.jump_speed,
+            move_speed: self.move_speed,
+            coyote_time: self.coyote_time,
+            jump_buffer_time: self.jump_buffer_time,
+            jump_cut_multiplier: self.jump_cut_multiplier,
+            rise_gravity_scale: self.rise_gravity_scale,
+            fall_gravity_scale: self.fall_gravity_scale,
+            acceleration: self.acceleration,
+            deceleration: self.deceleration,
+            air_acceleration: self.air_acceleration,
+            can_wall_slide: self.can_wall_slide,
+            can_wall_jump: self.can_wall_jump,
+            can_ledge_grab: self.can_ledge_grab,
+            can_double_jump: self.can_double_jump,
+            wall_slide_speed: self.wall_slide_speed,
+            wall_jump_velocity: self.wall_jump_velocity,
+            air_jumps: self.air_jumps,
+            physics: self.physics(),
+        }
+    }
+
+    /// Flips the player to face the way they are being steered.
+    fn face(&mut self, input: &FrameInput) {
+        let transform = self.base().get_transform();
+        let scale = self.base().get_scale();
+        let (left, right) = (input.left, input.right);
+
+        if (left && transform.scale().y > 0.) && !right {
+            self.base_mut()
+                .set_scale(Vector2::new(scale.x * -1., scale.y));
+        }
+
+        if (right && transform.scale().y < 0.) && !left {
+            self.base_mut()
+                .set_scale(Vector2::new(scale.x * -1., scale.y));
+        }
+    }
+
+    /// Whether the wall the player is touching ends within
+    /// `ledge_grab_height` above them.
+    fn at_ledge(&mut self, wall_normal: Vector2) -> bool {
+        let mut above = self.base().get_global_transform();
+        above.origin.y -= self.ledge_grab_height;
+        !self.base_mut().test_move(above, -wall_normal * 2.)
+    }
+}
@@ -0,0 +1,382 @@
+use godot::builtin::Vector2;
+
+use super::input::FrameInput;
+use crate::classes::physics_profile::PhysicsValues;
+
+/// Movement tuning, copied from the player's exports every frame.
+#[derive(Clone, Copy, Debug)]
+pub struct MovementParams {
+    pub jump_speed: f32,
+    pub move_speed: f32,
+    pub coyote_time: f32,
+    pub jump_buffer_time: f32,
+    pub jump_cut_multiplier: f32,
+    pub rise_gravity_scale: f32,
+    pub fall_gravity_scale: f32,
+    pub acceleration: f32,
+    pub deceleration: f32,
+    pub air_acceleration: f32,
+    pub can_wall_slide: bool,
+    pub can_wall_jump: bool,
+    pub can_ledge_grab: bool,
+    pub can_double_jump: bool,
+    pub wall_slide_speed: f32,
+    pub wall_jump_velocity: Vector2,
+    pub air_jumps: u32,
+    pub physics: PhysicsValues,
+}
+
+/// What the player's body was touching after the last move.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Contacts {
+    pub on_floor: bool,
+    /// Touching a wall and nothing else.
+    pub on_wall: bool,
+    pub wall_normal: Vector2,
+}
+
+/// The player's movement logic, kept free of engine calls so it can be
+/// stepped frame by frame from a scripted [`InputSource`].
+///
+/// [`InputSource`]: super::input::InputSource
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Movement {
+    pub coyote_timer: f32,
+    pub jump_buffer_timer: f32,
+    pub jumping: bool,
+    pub air_jumps_left: u32,
+    pub wall_sliding: bool,
+    pub ledge_grabbing: bool,
+    pub ledge_normal_x: f32,
+}
+
+impl Movement {
+    /// Applies gravity and updates the grounded timers, returning the new
+    /// velocity.
+    pub fn fall(
+        &mut self,
+        params: &MovementParams,
+        contacts: Contacts,
+        velocity: Vector2,
+        delta: f32,
+    ) -> Vector2 {
+        let mut velocity = velocity;
+        if contacts.on_floor {
+            self.coyote_timer = params.coyote_time;
+            self.jumping = false;
+            self.air_jumps_left = params.air_jumps;
+            self.ledge_grabbing = false;
+        } else if !self.ledge_grabbing {
+            self.coyote_timer -= delta;
+            let gravity_scale = if velocity.y < 0. {
+                params.rise_gravity_scale
+            } else {
+                params.fall_gravity_scale
+            };
+            velocity.y += params.physics.gravity * gravity_scale * delta;
+            velocity.y = velocity.y.min(params.physics.terminal_velocity);
+        }
+        velocity
+    }
+
+    /// Applies a frame of input, returning the new velocity. `at_ledge` is
+    /// only asked, with the wall normal, when a ledge grab is possible, since
+    /// finding out needs the physics server.
+    pub fn steer(
+        &mut self,
+        params: &MovementParams,
+        input: &FrameInput,
+        contacts: Contacts,
+        velocity: Vector2,
+        delta: f32,
+        at_ledge: impl FnOnce(Vector2) -> bool,
+    ) -> Vector2 {
+        let mut velocity = velocity;
+        let direction = input.direction;
+        let wall_normal = contacts.wall_normal;
+        let pushing_wall = contacts.on_wall && direction * wall_normal.x < 0.;
+
+        if input.jump_pressed {
+            self.jump_buffer_timer = params.jump_buffer_time;
+        } else {
+            self.jump_buffer_timer -= delta;
+        }
+
+        // Hanging from a ledge: climb with jump, let go by pushing away.
+        if self.ledge_grabbing {
+            velocity = Vector2::ZERO;
+            if self.jump_buffer_timer > 0. {
+                velocity.y = params.jump_speed;
+                self.jump_buffer_timer = 0.;
+                self.jumping = true;
+                self.ledge_grabbing = false;
+            } else if direction * self.ledge_normal_x > 0. {
+                self.ledge_grabbing = false;
+            }
+            return velocity;
+        }
+
+        let rate = if !contacts.on_floor {
+            params.air_acceleration * params.physics.air_control
+        } else if direction != 0. {
+            params.acceleration
+        } else {
+            params.deceleration * params.physics.friction
+        };
+        velocity.x =
+            approach(velocity.x, direction * params.move_speed, rate * delta);
+
+        if self.jump_buffer_timer > 0. {
+            let jumped = if self.coyote_timer > 0. {
+                velocity.y = params.jump_speed;
+                true
+            } else if contacts.on_wall && params.can_wall_jump {
+                velocity = Vector2::new(
+                    wall_normal.x * params.wall_jump_velocity.x,
+                    params.wall_jump_velocity.y,
+                );
+                true
+            } else if params.can_double_jump && self.air_jumps_left > 0 {
+                self.air_jumps_left -= 1;
+                velocity.y = params.jump_speed;
+                true
+            } else {
+                false
+            };
+            if jumped {
+                self.jump_buffer_timer = 0.;
+                self.coyote_timer = 0.;
+                self.jumping = true;
+            }
+        }
+
+        // Releasing jump while still rising cuts the jump short.
+        if self.jumping && velocity.y < 0. && !input.jump_held {
+            velocity.y *= params.jump_cut_multiplier;
+            self.jumping = false;
+        }
+
+        self.wall_sliding =
+            params.can_wall_slide && pushing_wall && velocity.y > 0.;
+        if self.wall_sliding {
+            velocity.y = velocity.y.min(params.wall_slide_speed);
+        }
+
+        if params.can_ledge_grab
+            && pushing_wall
+            && velocity.y > 0.
+            && at_ledge(wall_normal)
+        {
+            self.ledge_grabbing = true;
+            self.ledge_normal_x = wall_normal.x;
+            velocity = Vector2::ZERO;
+        }
+
+        velocity
+    }
+}
+
+/// Moves `current` towards `target` by at most `step`.
+fn approach(current: f32, target: f32, step: f32) -> f32 {
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::player::{InputSource, ScriptedInput};
+
+    /// A power of two, so the per-frame steps below are exact.
+    const DELTA: f32 = 1. / 64.;
+
+    fn params() -> MovementParams {
+        MovementParams {
+            jump_speed: -300.,
+            move_speed: 250.,
+            coyote_time: 0.1,
+            jump_buffer_time: 0.1,
+            jump_cut_multiplier: 0.5,
+            rise_gravity_scale: 1.,
+            fall_gravity_scale: 1.5,
+            acceleration: 2000.,
+            deceleration: 2500.,
+            air_acceleration: 1200.,
+            can_wall_slide: false,
+            can_wall_jump: false,
+            can_ledge_grab: false,
+            can_double_jump: false,
+            wall_slide_speed: 80.,
+            wall_jump_velocity: Vector2::new(250., -300.),
+            air_jumps: 1,
+            physics: PhysicsValues::default(),
+        }
+    }
+
+    fn run() -> FrameInput {
+        FrameInput {
+            direction: 1.,
+            right: true,
+            ..Default::default()
+        }
+    }
+
+    fn jump() -> FrameInput {
+        FrameInput {
+            jump_pressed: true,
+            jump_held: true,
+            ..Default::default()
+        }
+    }
+
+    fn hold_jump() -> FrameInput {
+        FrameInput {
+            jump_held: true,
+            ..Default::default()
+        }
+    }
+
+    /// Stand-in for the player's body, with an endless floor at y = 0 that
+    /// can be taken away.
+    struct Body {
+        params: MovementParams,
+        movement: Movement,
+        position: Vector2,
+        velocity: Vector2,
+        floor: bool,
+    }
+
+    impl Body {
+        fn new(params: MovementParams) -> Self {
+            Self {
+                params,
+                movement: Movement::default(),
+                position: Vector2::ZERO,
+                velocity: Vector2::ZERO,
+                floor: true,
+            }
+        }
+
+        /// Runs one physics frame the way the player does, without any
+        /// walls to grab.
+        fn step(&mut self, input: &FrameInput) {
+            let contacts = Contacts {
+                on_floor: self.floor && self.position.y >= 0.,
+                ..Default::default()
+            };
+            let velocity = self.movement.fall(
+                &self.params,
+                contacts,
+                self.velocity,
+                DELTA,
+            );
+            self.velocity = self.movement.steer(
+                &self.params,
+                input,
+                contacts,
+                velocity,
+                DELTA,
+                |_| false,
+            );
+            self.position += self.velocity * DELTA;
+            if self.floor && self.position.y > 0. {
+                self.position.y = 0.;
+                self.velocity.y = 0.;
+            }
+        }
+
+        /// Steps through every frame of `input`.
+        fn play(&mut self, mut input: ScriptedInput) {
+            while !input.is_finished() {
+                let frame = input.next_frame();
+                self.step(&frame);
+            }
+        }
+    }
+
+    #[test]
+    fn running_accelerates_to_move_speed() {
+        let mut body = Body::new(params());
+        body.play(ScriptedInput::new([run()]));
+        assert_eq!(body.velocity, Vector2::new(31.25, 0.));
+        assert_eq!(body.position, Vector2::new(0.488_281_25, 0.));
+
+        body.play(ScriptedInput::new([run(); 9]));
+        assert_eq!(body.velocity, Vector2::new(250., 0.));
+        assert_eq!(body.position.x, 0.488_281_25 * 36. + 250. * 2. * DELTA);
+    }
+
+    #[test]
+    fn letting_go_decelerates() {
+        let mut body = Body::new(params());
+        body.play(ScriptedInput::new([run(); 8]));
+        body.play(ScriptedInput::new([FrameInput::default()]));
+        assert_eq!(body.velocity.x, 250. - 2500. * DELTA);
+    }
+
+    #[test]
+    fn jumping_rises_then_falls() {
+        let mut body = Body::new(params());
+        body.play(ScriptedInput::new([jump()]));
+        assert_eq!(body.velocity, Vector2::new(0., -300.));
+        assert_eq!(body.position.y, -300. * DELTA);
+
+        body.play(ScriptedInput::new([hold_jump()]));
+        assert_eq!(body.velocity.y, -300. + 1000. * DELTA);
+        assert!(body.movement.jumping);
+    }
+
+    #[test]
+    fn releasing_jump_cuts_it_short() {
+        let mut body = Body::new(params());
+        body.play(ScriptedInput::new([jump(), FrameInput::default()]));
+        assert_eq!(body.velocity.y, (-300. + 1000. * DELTA) * 0.5);
+        assert!(!body.movement.jumping);
+    }
+
+    #[test]
+    fn jump_pressed_just_before_landing_is_buffered() {
+        let mut body = Body::new(params());
+        body.position.y = -0.5;
+        body.velocity.y = 64.;
+        body.play(ScriptedInput::new([jump()]));
+        assert_eq!(body.position.y, 0.);
+        assert_eq!(body.velocity.y, 0.);
+
+        body.play(ScriptedInput::new([hold_jump()]));
+        assert_eq!(body.velocity.y, -300.);
+    }
+
+    #[test]
+    fn jumping_just_after_leaving_the_floor_still_works() {
+        let mut body = Body::new(params());
+        body.play(ScriptedInput::new([FrameInput::default()]));
+        body.floor = false;
+        body.play(ScriptedInput::new([FrameInput::default(), jump()]));
+        assert_eq!(body.velocity.y, -300.);
+
+        let mut late = Body::new(params());
+        late.play(ScriptedInput::new([FrameInput::default()]));
+        late.floor = false;
+        late.play(ScriptedInput::new([FrameInput::default(); 7]));
+        late.play(ScriptedInput::new([jump()]));
+        assert!(late.velocity.y > 0.);
+    }
+
+    #[test]
+    fn double_jump_uses_up_air_jumps() {
+        let mut body = Body::new(MovementParams {
+            can_double_jump: true,
+            ..params()
+        });
+        body.play(ScriptedInput::new([jump(), jump()]));
+        assert_eq!(body.velocity.y, -300.);
+        assert_eq!(body.movement.air_jumps_left, 0);
+
+        body.play(ScriptedInput::new([jump()]));
+        assert_eq!(body.velocity.y, -300. + 1000. * DELTA);
+    }
+}
@@ -0,0 +1,194 @@
+/// Horizontal speed below which a grounded player counts as standing still.
+const RUN_THRESHOLD: f32 = 10.;
+
+/// High level state of the player, driving both animation and whether
+/// movement input is accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerState {
+    Idle,
+    Run,
+    Jump,
+    Fall,
+    WallSlide,
+    LedgeGrab,
+    Interact,
+    Dead,
+}
+
+/// Everything the state machine needs to know about the player for a single
+/// physics frame. Kept free of engine types so it can be driven from tests.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StateInput {
+    pub on_floor: bool,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub interacting: bool,
+    pub wall_sliding: bool,
+    pub ledge_grabbing: bool,
+    pub dead: bool,
+}
+
+impl PlayerState {
+    pub fn next(self, input: &StateInput) -> Self {
+        if input.dead {
+            return Self::Dead;
+        }
+        if input.interacting && input.on_floor {
+            return Self::Interact;
+        }
+        if input.ledge_grabbing {
+            return Self::LedgeGrab;
+        }
+        if !input.on_floor {
+            if input.wall_sliding {
+                return Self::WallSlide;
+            }
+            return if input.velocity_y < 0. {
+                Self::Jump
+            } else {
+                Self::Fall
+            };
+        }
+        if input.velocity_x.abs() > RUN_THRESHOLD {
+            Self::Run
+        } else {
+            Self::Idle
+        }
+    }
+
+    pub fn allows_movement(self) -> bool {
+        !matches!(self, Self::Interact | Self::Dead)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Idle => "Idle",
+            Self::Run => "Run",
+            Self::Jump => "Jump",
+            Self::Fall => "Fall",
+            Self::WallSlide => "WallSlide",
+            Self::LedgeGrab => "LedgeGrab",
+            Self::Interact => "Interact",
+            Self::Dead => "Dead",
+        }
+    }
+
+    /// Animation to play when the `AnimationPlayer` has nothing named after
+    /// this state.
+    pub fn fallback_animation(self) -> &'static str {
+        match self {
+            Self::Jump | Self::Fall | Self::WallSlide | Self::LedgeGrab => {
+                "Jump"
+            }
+            _ => "Idle",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grounded(velocity_x: f32) -> StateInput {
+        StateInput {
+            on_floor: true,
+            velocity_x,
+            ..Default::default()
+        }
+    }
+
+    fn airborne(velocity_y: f32) -> StateInput {
+        StateInput {
+            velocity_y,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn standing_still_is_idle() {
+        assert_eq!(PlayerState::Run.next(&grounded(0.)), PlayerState::Idle);
+        assert_eq!(
+            PlayerState::Idle.next(&grounded(RUN_THRESHOLD)),
+            PlayerState::Idle
+        );
+    }
+
+    #[test]
+    fn moving_on_the_floor_is_running() {
+        assert_eq!(PlayerState::Idle.next(&grounded(200.)), PlayerState::Run);
+        assert_eq!(PlayerState::Idle.next(&grounded(-200.)), PlayerState::Run);
+    }
+
+    #[test]
+    fn rising_is_jumping_and_dropping_is_falling() {
+        assert_eq!(PlayerState::Idle.next(&airborne(-300.)), PlayerState::Jump);
+        assert_eq!(PlayerState::Jump.next(&airborne(300.)), PlayerState::Fall);
+        assert_eq!(PlayerState::Run.next(&airborne(0.)), PlayerState::Fall);
+    }
+
+    #[test]
+    fn landing_ends_the_fall() {
+        assert_eq!(PlayerState::Fall.next(&grounded(0.)), PlayerState::Idle);
+        assert_eq!(PlayerState::Fall.next(&grounded(200.)), PlayerState::Run);
+    }
+
+    #[test]
+    fn wall_slide_only_in_the_air() {
+        let sliding = StateInput {
+            wall_sliding: true,
+            velocity_y: 50.,
+            ..Default::default()
+        };
+        assert_eq!(PlayerState::Fall.next(&sliding), PlayerState::WallSlide);
+
+        let on_floor = StateInput {
+            on_floor: true,
+            ..sliding
+        };
+        assert_eq!(PlayerState::WallSlide.next(&on_floor), PlayerState::Idle);
+    }
+
+    #[test]
+    fn ledge_grab_beats_falling() {
+        let grabbing = StateInput {
+            ledge_grabbing: true,
+            wall_sliding: true,
+            ..Default::default()
+        };
+        assert_eq!(PlayerState::Fall.next(&grabbing), PlayerState::LedgeGrab);
+    }
+
+    #[test]
+    fn interacting_needs_the_floor() {
+        let interacting = StateInput {
+            interacting: true,
+            ..grounded(0.)
+        };
+        assert_eq!(PlayerState::Idle.next(&interacting), PlayerState::Interact);
+        let in_air = StateInput {
+            on_floor: false,
+            ..interacting
+        };
+        assert_eq!(PlayerState::Jump.next(&in_air), PlayerState::Fall);
+    }
+
+    #[test]
+    fn death_overrides_everything() {
+        let dead = StateInput {
+            dead: true,
+            interacting: true,
+            ledge_grabbing: true,
+            ..grounded(200.)
+        };
+        for state in [
+            PlayerState::Idle,
+            PlayerState::Run,
+            PlayerState::Jump,
+            PlayerState::WallSlide,
+        ] {
+            assert_eq!(state.next(&dead), PlayerState::Dead);
+        }
+        assert!(!PlayerState::Dead.allows_movement());
+        assert!(PlayerState::Run.allows_movement());
+    }
+}
@@ -0,0 +1,148 @@
+use godot::classes::node::ProcessMode;
+use godot::classes::{INode2D, Node2D};
+use godot::prelude::*;
+
+use super::Replay;
+use crate::classes::graphs::bar::BarGraph;
+
+/// Translucent copy of the player following a recorded [`Replay`], optionally
+/// animating the level's bars along with it.
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct ReplayGhost {
+    base: Base<Node2D>,
+    /// Replay file to play when the ghost is placed in a level by hand.
+    #[export]
+    replay_path: GString,
+    /// Also play back the recorded bar changes. The level's minigame is
+    /// switched off while this is on so it doesn't fight the replay.
+    #[export]
+    drive_bars: bool,
+    #[export]
+    opacity: f32,
+    replay: Option<Replay>,
+    frame: usize,
+    next_bar_change: usize,
+    sprite: Option<Gd<Node2D>>,
+}
+
+#[godot_api]
+impl INode2D for ReplayGhost {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            replay_path: GString::new(),
+            drive_bars: false,
+            opacity: 0.4,
+            replay: None,
+            frame: 0,
+            next_bar_change: 0,
+            sprite: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let opacity = self.opacity;
+        self.base_mut()
+            .set_modulate(Color::from_rgba(1., 1., 1., opacity));
+        self.base_mut().set_as_top_level(true);
+
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        if let Some(player) = tree.get_first_node_in_group("player".into()) {
+            let sprite = player
+                .try_get_node_as::<Node2D>("CollisionShape2D/Sprite2D")
+                .and_then(|sprite| sprite.duplicate())
+                .and_then(|sprite| sprite.try_cast::<Node2D>().ok());
+            if let Some(sprite) = sprite {
+                self.base_mut().add_child(sprite.clone());
+                self.sprite = Some(sprite);
+            }
+        }
+
+        if !self.replay_path.is_empty() {
+            if let Some(replay) = Replay::load(self.replay_path.clone()) {
+                self.play(replay);
+            }
+        }
+    }
+
+    fn physics_process(&mut self, _delta: f64) {
+        let Some(ref replay) = self.replay else {
+            return;
+        };
+        let Some(frame) = replay.frames.get(self.frame).copied() else {
+            self.base_mut().hide();
+            return;
+        };
+        let changes: Vec<_> = replay.bar_changes[self.next_bar_change..]
+            .iter()
+            .take_while(|change| change.frame as usize <= self.frame)
+            .copied()
+            .collect();
+        self.frame += 1;
+        self.next_bar_change += changes.len();
+
+        self.base_mut().set_global_position(frame.position);
+        if let Some(ref mut sprite) = self.sprite {
+            let input = frame.input;
+            let mut scale = sprite.get_scale();
+            if input.left && !input.right {
+                scale.x = -scale.x.abs();
+            } else if input.right && !input.left {
+                scale.x = scale.x.abs();
+            }
+            sprite.set_scale(scale);
+        }
+
+        if !self.drive_bars || changes.is_empty() {
+            return;
+        }
+        let Some(mut bar_graph) = BarGraph::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        let mut bar_graph = bar_graph.bind_mut();
+        for change in changes {
+            bar_graph.set_bar_value(change.index, change.value);
+        }
+    }
+}
+
+#[godot_api]
+impl ReplayGhost {
+    /// Adds a ghost playing `replay` to the current scene.
+    pub fn spawn(node: Gd<Node>, replay: Replay) {
+        let Some(tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        let Some(mut scene) = tree.get_current_scene() else {
+            godot_error!("Could not get current scene");
+            return;
+        };
+        let mut ghost = Self::new_alloc();
+        scene.add_child(ghost.clone());
+        ghost.bind_mut().play(replay);
+    }
+
+    pub fn play(&mut self, replay: Replay) {
+        self.frame = 0;
+        self.next_bar_change = 0;
+        self.replay = Some(replay);
+        self.base_mut().show();
+        if !self.drive_bars {
+            return;
+        }
+        let Some(mut tree) = self.base().get_tree() else {
+            return;
+        };
+        if let Some(mut minigame) =
+            tree.get_first_node_in_group("minigame".into())
+        {
+            minigame.set_process_mode(ProcessMode::DISABLED);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{DirAccess, FileAccess};
+use godot::global::Error;
+use godot::prelude::*;
+
+use super::player::FrameInput;
+
+mod ghost;
+mod recorder;
+
+pub use recorder::ReplayRecorder;
+
+pub const REPLAYS_DIR: &str = "user://replays/";
+
+const MAGIC: &[u8; 4] = b"RPLY";
+const VERSION: u8 = 1;
+
+const LEFT: u8 = 1;
+const RIGHT: u8 = 1 << 1;
+const JUMP_PRESSED: u8 = 1 << 2;
+const JUMP_HELD: u8 = 1 << 3;
+
+/// What the player did and where they were on one physics frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplayFrame {
+    pub input: FrameInput,
+    pub position: Vector2,
+}
+
+/// A bar in the level's graph changing value during a run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarChange {
+    pub frame: u32,
+    pub index: u32,
+    pub value: f32,
+}
+
+/// A recorded run through a level.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    pub level: String,
+    pub frames: Vec<ReplayFrame>,
+    pub bar_changes: Vec<BarChange>,
+}
+
+impl Replay {
+    pub fn new(level: String) -> Self {
+        Self {
+            level,
+            ..Default::default()
+        }
+    }
+
+    /// Where the replay for `level` is kept, one per level.
+    pub fn path_for(level: &str) -> GString {
+        let name = level
+            .rsplit('/')
+            .next()
+            .unwrap_or(level)
+            .trim_end_matches(".tscn");
+        format!("{}{}.replay", REPLAYS_DIR, name).into()
+    }
+
+    pub fn load(path: GString) -> Option<Self> {
+        if !FileAccess::file_exists(path.clone()) {
+            return None;
+        }
+        let bytes = FileAccess::get_file_as_bytes(path.clone());
+        let replay = Self::decode(bytes.as_slice());
+        if replay.is_none() {
+            godot_error!("{} is not a valid replay", path);
+        }
+        replay
+    }
+
+    pub fn save(&self, path: GString) {
+        let err = DirAccess::make_dir_recursive_absolute(REPLAYS_DIR.into());
+        if err != Error::OK {
+            godot_error!("Failed to create {}: {:?}", REPLAYS_DIR, err);
+            return;
+        }
+        let Some(mut file) = FileAccess::open(path.clone(), ModeFlags::WRITE)
+        else {
+            godot_error!("Failed to open {} for writing", path);
+            return;
+        };
+        file.store_buffer(PackedByteArray::from(self.encode().as_slice()));
+        file.close();
+    }
+
+    /// Packs the replay into 10 bytes per frame plus 9 per bar change, all
+    /// little endian.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            16 + self.level.len()
+                + self.frames.len() * 10
+                + self.bar_changes.len() * 9,
+        );
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.level.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(self.level.as_bytes());
+
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            let input = frame.input;
+            let direction = (input.direction.clamp(-1., 1.) * 127.).round();
+            bytes.push(direction as i8 as u8);
+            let mut flags = 0;
+            for (set, flag) in [
+                (input.left, LEFT),
+                (input.right, RIGHT),
+                (input.jump_pressed, JUMP_PRESSED),
+                (input.jump_held, JUMP_HELD),
+            ] {
+                if set {
+                    flags |= flag;
+                }
+            }
+            bytes.push(flags);
+            bytes.extend_from_slice(&frame.position.x.to_le_bytes());
+            bytes.extend_from_slice(&frame.position.y.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.bar_changes.len() as u32).to_le_bytes());
+        for change in &self.bar_changes {
+            bytes.extend_from_slice(&change.frame.to_le_bytes());
+            bytes.push(change.index as u8);
+            bytes.extend_from_slice(&change.value.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != MAGIC || reader.u8()? != VERSION {
+            return None;
+        }
+        let level_len = u16::from_le_bytes(reader.array()?) as usize;
+        let level = String::from_utf8(reader.take(level_len)?.to_vec()).ok()?;
+
+        let frame_count = reader.u32()?;
+        let mut frames = Vec::with_capacity(frame_count as usize);
+        for _ in 0..frame_count {
+            let direction = reader.u8()? as i8 as f32 / 127.;
+            let flags = reader.u8()?;
+            let position = Vector2::new(reader.f32()?, reader.f32()?);
+            frames.push(ReplayFrame {
+                input: FrameInput {
+                    direction,
+                    left: flags & LEFT != 0,
+                    right: flags & RIGHT != 0,
+                    jump_pressed: flags & JUMP_PRESSED != 0,
+                    jump_held: flags & JUMP_HELD != 0,
+                },
+                position,
+            });
+        }
+
+        let change_count = reader.u32()?;
+        let mut bar_changes = Vec::with_capacity(change_count as usize);
+        for _ in 0..change_count {
+            bar_changes.push(BarChange {
+                frame: reader.u32()?,
+                index: reader.u8()? as u32,
+                value: reader.f32()?,
+            });
+        }
+
+        Some(Self {
+            level,
+            frames,
+            bar_changes,
+        })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < count {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> { Some(self.take(1)?[0]) }
+
+    fn u32(&mut self) -> Option<u32> { Some(u32::from_le_bytes(self.array()?)) }
+
+    fn f32(&mut self) -> Option<f32> { Some(f32::from_le_bytes(self.array()?)) }
+}
@@ -0,0 +1,162 @@
+use godot::classes::{INode, Node};
+use godot::prelude::*;
+
+use super::ghost::ReplayGhost;
+use super::{BarChange, Replay, ReplayFrame};
+use crate::classes::graphs::bar::BarGraph;
+use crate::classes::player::Player;
+use crate::classes::scene_manager::SceneManager;
+
+/// Autoload that records every run through a level. A run that reaches the
+/// exit faster than the one saved for the level replaces it in
+/// [`REPLAYS_DIR`](super::REPLAYS_DIR).
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct ReplayRecorder {
+    base: Base<Node>,
+    /// Race a ghost of the best run whenever a level starts.
+    #[export]
+    show_ghost: bool,
+    replay: Option<Replay>,
+    player: Option<Gd<Player>>,
+    /// The current run made it to the exit.
+    completed: bool,
+}
+
+#[godot_api]
+impl INode for ReplayRecorder {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            show_ghost: true,
+            replay: None,
+            player: None,
+            completed: false,
+        }
+    }
+
+    fn ready(&mut self) {
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        let this = self.to_gd();
+        scene_manager.connect(
+            "scene_changing".into(),
+            Callable::from_object_method(&this, "scene_changing"),
+        );
+        scene_manager.connect(
+            "scene_changed".into(),
+            Callable::from_object_method(&this, "scene_changed"),
+        );
+    }
+
+    fn physics_process(&mut self, _delta: f64) {
+        let Some(ref mut replay) = self.replay else {
+            return;
+        };
+        let Some(ref player) = self.player else {
+            return;
+        };
+        if !player.is_instance_valid() {
+            return;
+        }
+        replay.frames.push(ReplayFrame {
+            input: player.bind().frame_input(),
+            position: player.get_global_position(),
+        });
+    }
+}
+
+#[godot_api]
+impl ReplayRecorder {
+    /// Marks the run through the current level as finished, so it's kept
+    /// if it's the best yet.
+    pub fn complete(node: Gd<Node>) {
+        let Some(root) = node.get_tree().and_then(|tree| tree.get_root())
+        else {
+            return;
+        };
+        if let Some(mut recorder) =
+            root.try_get_node_as::<Self>("ReplayRecorder")
+        {
+            recorder.bind_mut().completed = true;
+        }
+    }
+
+    #[func]
+    fn scene_changing(&mut self, _from: GString, _to: GString) {
+        self.player = None;
+        let completed = std::mem::take(&mut self.completed);
+        let Some(replay) = self.replay.take() else {
+            return;
+        };
+        if !completed || replay.frames.is_empty() {
+            return;
+        }
+        let path = Replay::path_for(&replay.level);
+        if let Some(best) = Replay::load(path.clone()) {
+            if best.frames.len() <= replay.frames.len() {
+                return;
+            }
+        }
+        replay.save(path);
+    }
+
+    #[func]
+    fn scene_changed(&mut self, path: GString) {
+        let Some(scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        if !scene_manager.bind().in_level() {
+            return;
+        }
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        let Some(player) = tree.get_first_node_in_group("player".into()) else {
+            godot_error!("No player to record in {}", path);
+            return;
+        };
+        self.player = player.try_cast::<Player>().ok();
+
+        if let Some(mut bar_graph) =
+            BarGraph::find(self.base().clone().upcast())
+        {
+            bar_graph.connect(
+                "bar_changed".into(),
+                Callable::from_object_method(&self.to_gd(), "bar_changed"),
+            );
+        }
+
+        let level = path.to_string();
+        if self.show_ghost {
+            if let Some(previous) = Replay::load(Replay::path_for(&level)) {
+                ReplayGhost::spawn(self.base().clone().upcast(), previous);
+            }
+        }
+        self.replay = Some(Replay::new(level));
+    }
+
+    #[func]
+    fn bar_changed(&mut self, index: u32, value: f32) {
+        // Changes a ghost plays back aren't part of this run.
+        let playing_back = BarGraph::find(self.base().clone().upcast())
+            .is_some_and(|bar_graph| bar_graph.bind().is_playing_back());
+        if playing_back {
+            return;
+        }
+        let Some(ref mut replay) = self.replay else {
+            return;
+        };
+        replay.bar_changes.push(BarChange {
+            frame: replay.frames.len() as u32,
+            index,
+            value,
+        });
+    }
+}
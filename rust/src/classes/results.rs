@@ -0,0 +1,155 @@
+use godot::classes::node::ProcessMode;
+use godot::classes::{
+    Button, CanvasLayer, Control, IControl, InputEvent, Label, PackedScene,
+    ResourceLoader, VBoxContainer,
+};
+use godot::prelude::*;
+
+use super::locale::{format_number, tr, tr_args};
+use super::save_data::SaveData;
+use super::scene_manager::SceneManager;
+use super::score::{LevelScoring, RunStats};
+
+pub const RESULTS: &str = "res://scenes/results.tscn";
+
+/// End of level screen shown when leaving a level through a `Door`. Pauses
+/// the level underneath until the player continues.
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct Results {
+    base: Base<Control>,
+    on_continue: Option<Callable>,
+}
+
+#[godot_api]
+impl IControl for Results {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            on_continue: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        // Keep the pause menu from unpausing the level under the results.
+        if event.is_action_pressed("Pause".into()) {
+            self.base().get_viewport().unwrap().set_input_as_handled();
+        }
+    }
+}
+
+#[godot_api]
+impl Results {
+    /// Scores the current level, records it in the save data and shows the
+    /// results over it. `on_continue` is called once the player moves on.
+    pub fn open(node: Gd<Node>, on_continue: Callable) {
+        let Some(mut tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        let Some(mut scene) = tree.get_current_scene() else {
+            godot_error!("Could not get current scene");
+            return;
+        };
+        let Some(packed) = ResourceLoader::singleton().load(RESULTS.into())
+        else {
+            godot_error!("Failed to load {}", RESULTS);
+            return;
+        };
+        let Ok(packed) = packed.try_cast::<PackedScene>() else {
+            godot_error!("Failed to cast {} as a PackedScene", RESULTS);
+            return;
+        };
+        let Some(results) = packed.instantiate() else {
+            godot_error!("Failed to instantiate {}", RESULTS);
+            return;
+        };
+        let Ok(mut results) = results.try_cast::<Self>() else {
+            godot_error!("{} is not a Results screen", RESULTS);
+            return;
+        };
+
+        let (stats, score, stars) = LevelScoring::rate(node.clone());
+        let level = match SceneManager::find(node) {
+            Some(scene_manager) => scene_manager.bind().current_scene_path(),
+            None => GString::new(),
+        };
+        let mut save_data = SaveData::load_or_default();
+        let best = save_data.bind_mut().record(level, score, stars);
+        save_data.bind().save();
+
+        let mut layer = CanvasLayer::new_alloc();
+        layer.set_layer(5);
+        layer.add_child(results.clone());
+        scene.add_child(layer);
+        tree.set_pause(true);
+
+        let mut results = results.bind_mut();
+        results.on_continue = Some(on_continue);
+        results.show_stats(&stats, score, stars, best);
+    }
+
+    #[func]
+    fn continue_pressed(&mut self) {
+        // Taken so a double click can't move on twice.
+        let Some(on_continue) = self.on_continue.take() else {
+            return;
+        };
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.set_pause(false);
+        }
+        on_continue.callv(VariantArray::new());
+    }
+
+    fn show_stats(
+        &mut self,
+        stats: &RunStats,
+        score: u32,
+        stars: u32,
+        best: bool,
+    ) {
+        let mut rows = VBoxContainer::new_alloc();
+        let seconds = stats.time as u32;
+        let time = format!("{}:{:02}", seconds / 60, seconds % 60);
+        let accuracy = stats.accuracy().map(|accuracy| {
+            let accuracy = format_number(accuracy * 100., 0);
+            tr_args("RESULTS_ACCURACY", &[&accuracy])
+        });
+        let lines = [
+            Some(tr("RESULTS_TITLE")),
+            Some(tr_args("RESULTS_TIME", &[&time])),
+            Some(tr_args("RESULTS_DEATHS", &[&stats.deaths])),
+            Some(tr_args("RESULTS_BUDGET", &[&stats.budget_left])),
+            Some(tr_args("RESULTS_HINTS", &[&stats.hints])),
+            accuracy,
+            Some(tr_args("RESULTS_SCORE", &[&format_number(score as f64, 0)])),
+            Some(tr_args("RESULTS_STARS", &[&"*".repeat(stars as usize)])),
+        ];
+        for line in lines.into_iter().flatten() {
+            let mut label = Label::new_alloc();
+            label.set_text(line.into());
+            rows.add_child(label);
+        }
+        if best {
+            let mut label = Label::new_alloc();
+            label.set_text("RESULTS_NEW_BEST".into());
+            rows.add_child(label);
+        }
+
+        let mut next = Button::new_alloc();
+        next.set_text("RESULTS_CONTINUE".into());
+        next.connect(
+            "pressed".into(),
+            Callable::from_object_method(&self.to_gd(), "continue_pressed"),
+        );
+        rows.add_child(next.clone());
+
+        let mut center = self.base().get_node_as::<Control>("Center");
+        center.add_child(rows);
+        next.grab_focus();
+    }
+}
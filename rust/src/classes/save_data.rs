@@ -0,0 +1,140 @@
+use godot::classes::{IResource, Resource, ResourceLoader, ResourceSaver};
+use godot::global::Error;
+use godot::prelude::*;
+
+pub const SAVE_PATH: &str = "user://save.tres";
+
+/// Progress that persists between sessions, keyed by level scene path.
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct SaveData {
+    #[export]
+    best_scores: Dictionary,
+    #[export]
+    stars: Dictionary,
+    #[export]
+    speedrun_attempts: u32,
+    /// Time at the end of each level in the personal best run.
+    #[export]
+    speedrun_splits: PackedFloat64Array,
+    /// Fastest time ever taken on each level of a speedrun.
+    #[export]
+    speedrun_best_segments: PackedFloat64Array,
+    /// Ids of tutorials the player has finished or skipped.
+    #[export]
+    finished_tutorials: PackedStringArray,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for SaveData {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            best_scores: Dictionary::new(),
+            stars: Dictionary::new(),
+            speedrun_attempts: 0,
+            speedrun_splits: PackedFloat64Array::new(),
+            speedrun_best_segments: PackedFloat64Array::new(),
+            finished_tutorials: PackedStringArray::new(),
+            base,
+        }
+    }
+}
+
+#[godot_api]
+impl SaveData {
+    /// Loads the save file, starting fresh if there isn't one yet.
+    pub fn load_or_default() -> Gd<Self> {
+        let mut resource_loader = ResourceLoader::singleton();
+        if !resource_loader.exists(SAVE_PATH.into()) {
+            return Self::new_gd();
+        }
+        let Some(save_data) = resource_loader.load(SAVE_PATH.into()) else {
+            godot_error!("Failed to load save data");
+            return Self::new_gd();
+        };
+        let Ok(save_data) = save_data.try_cast::<Self>() else {
+            godot_error!("Failed to cast save data as SaveData");
+            return Self::new_gd();
+        };
+        save_data
+    }
+
+    #[func]
+    pub fn save(&self) {
+        let err = ResourceSaver::singleton()
+            .save_ex(self.to_gd().upcast())
+            .path(SAVE_PATH.into())
+            .done();
+        if err != Error::OK {
+            godot_error!("Failed to save game: {:?}", err);
+        }
+    }
+
+    #[func]
+    pub fn best_score(&self, level: GString) -> u32 {
+        self.best_scores.get(level).map_or(0, |score| score.to())
+    }
+
+    #[func]
+    pub fn get_stars(&self, level: GString) -> u32 {
+        self.stars.get(level).map_or(0, |stars| stars.to())
+    }
+
+    /// Keeps the best score and star count seen for `level`, returning
+    /// whether `score` is a new best.
+    #[func]
+    pub fn record(&mut self, level: GString, score: u32, stars: u32) -> bool {
+        if stars > self.get_stars(level.clone()) {
+            self.stars.set(level.clone(), stars);
+        }
+        if score <= self.best_score(level.clone()) {
+            return false;
+        }
+        self.best_scores.set(level, score);
+        true
+    }
+
+    pub fn start_speedrun(&mut self) { self.speedrun_attempts += 1; }
+
+    pub fn speedrun_attempts(&self) -> u32 { self.speedrun_attempts }
+
+    pub fn speedrun_splits(&self) -> &[f64] { self.speedrun_splits.as_slice() }
+
+    pub fn speedrun_best_segments(&self) -> &[f64] {
+        self.speedrun_best_segments.as_slice()
+    }
+
+    /// Records a finished speedrun given the time at the end of each level,
+    /// returning whether it is a new personal best.
+    pub fn record_speedrun(&mut self, splits: &[f64]) -> bool {
+        let mut previous = 0.;
+        for (idx, split) in splits.iter().enumerate() {
+            let segment = split - previous;
+            previous = *split;
+            match self.speedrun_best_segments.as_slice().get(idx).copied() {
+                Some(best) if best <= segment => {}
+                Some(_) => self.speedrun_best_segments.set(idx, segment),
+                None => self.speedrun_best_segments.push(segment),
+            }
+        }
+        let total = splits.last().copied().unwrap_or(f64::MAX);
+        let best = self.speedrun_splits.as_slice().last().copied();
+        if best.is_some_and(|best| best <= total) {
+            return false;
+        }
+        self.speedrun_splits = PackedFloat64Array::from(splits);
+        true
+    }
+
+    #[func]
+    pub fn tutorial_finished(&self, id: GString) -> bool {
+        self.finished_tutorials.contains(&id)
+    }
+
+    pub fn finish_tutorial(&mut self, id: GString) {
+        if !self.tutorial_finished(id.clone()) {
+            self.finished_tutorials.push(id);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use godot::classes::node::ProcessMode;
+use godot::classes::{INode, Node, PackedScene, ResourceLoader};
+use godot::global::Error;
+use godot::prelude::*;
+
+use super::level_state::LevelState;
+
+pub const MAIN_MENU: &str = "res://scenes/MainMenu.tscn";
+pub const CREDITS: &str = "res://scenes/credits.tscn";
+pub const FIRST_LEVEL: &str = "res://levels/level1.tscn";
+pub const LEVELS_DIR: &str = "res://levels/";
+pub const ENDLESS_LEVEL: &str = "res://levels/endless.tscn";
+
+/// Autoload that owns every scene transition in the game.
+///
+/// Registered in `project.godot` as `SceneManager`, so any node can reach it
+/// through [`SceneManager::find`].
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct SceneManager {
+    base: Base<Node>,
+    history: Vec<GString>,
+    preloading: Vec<GString>,
+    pending: Option<GString>,
+}
+
+#[godot_api]
+impl INode for SceneManager {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            history: Vec::new(),
+            preloading: Vec::new(),
+            pending: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+    }
+
+    fn process(&mut self, _delta: f64) {
+        let Some(pending) = self.pending.clone() else {
+            return;
+        };
+        // The tree swaps scenes at the end of the frame, so wait until the
+        // new scene is actually current before announcing it.
+        if self.current_scene_path() != pending {
+            return;
+        }
+        self.pending = None;
+        self.base_mut()
+            .emit_signal("scene_changed".into(), &[pending.to_variant()]);
+    }
+}
+
+#[godot_api]
+impl SceneManager {
+    #[signal]
+    fn scene_changing(from: GString, to: GString);
+
+    #[signal]
+    fn scene_changed(path: GString);
+
+    /// Looks up the `SceneManager` autoload from any node in the tree.
+    pub fn find(node: Gd<Node>) -> Option<Gd<Self>> {
+        let Some(tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return None;
+        };
+        let Some(root) = tree.get_root() else {
+            godot_error!("Could not get root");
+            return None;
+        };
+        let scene_manager = root.try_get_node_as::<Self>("SceneManager");
+        if scene_manager.is_none() {
+            godot_error!("SceneManager autoload is missing!");
+        }
+        scene_manager
+    }
+
+    #[func]
+    pub fn current_scene_path(&self) -> GString {
+        let Some(tree) = self.base().get_tree() else {
+            return GString::new();
+        };
+        match tree.get_current_scene() {
+            Some(scene) => scene.get_scene_file_path(),
+            None => GString::new(),
+        }
+    }
+
+    /// Whether the current scene is a playable level rather than a menu.
+    #[func]
+    pub fn in_level(&self) -> bool {
+        self.current_scene_path()
+            .to_string()
+            .starts_with(LEVELS_DIR)
+    }
+
+    /// Starts loading `path` on a background thread so a later
+    /// [`SceneManager::change_scene`] to it doesn't stall.
+    #[func]
+    pub fn preload(&mut self, path: GString) {
+        if self.preloading.contains(&path) {
+            return;
+        }
+        let err =
+            ResourceLoader::singleton().load_threaded_request(path.clone());
+        if err != Error::OK {
+            godot_error!("Failed to start preloading {}: {:?}", path, err);
+            return;
+        }
+        self.preloading.push(path);
+    }
+
+    #[func]
+    pub fn change_scene(&mut self, path: GString) {
+        let Some(scene) = self.load(path.clone()) else {
+            return;
+        };
+        self.transition(scene, true);
+    }
+
+    #[func]
+    pub fn change_scene_to_packed(&mut self, scene: Gd<PackedScene>) {
+        self.transition(scene, true);
+    }
+
+    /// Starts the current level over from scratch, dropping the checkpoint,
+    /// deaths, hints and time of the attempt so far. A generated level is
+    /// rebuilt from the same seed.
+    #[func]
+    pub fn restart(&mut self) {
+        if let Some(mut level_state) =
+            LevelState::find(self.base().clone().upcast())
+        {
+            level_state.bind_mut().reset();
+        }
+        self.reload();
+    }
+
+    /// Reloads the current scene, keeping the attempt's progress, e.g. to
+    /// respawn at the last checkpoint.
+    #[func]
+    pub fn reload(&mut self) {
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        let path = self.current_scene_path();
+        self.base_mut().emit_signal(
+            "scene_changing".into(),
+            &[path.to_variant(), path.to_variant()],
+        );
+        tree.reload_current_scene();
+        self.pending = Some(path);
+    }
+
+    /// Returns to the previous scene, returning `false` if there is no
+    /// history to go back to.
+    #[func]
+    pub fn back(&mut self) -> bool {
+        let Some(path) = self.history.pop() else {
+            return false;
+        };
+        let Some(scene) = self.load(path) else {
+            return false;
+        };
+        self.transition(scene, false);
+        true
+    }
+
+    #[func]
+    pub fn quit(&mut self) {
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        tree.quit();
+    }
+
+    fn load(&mut self, path: GString) -> Option<Gd<PackedScene>> {
+        let mut resource_loader = ResourceLoader::singleton();
+        let resource = if let Some(idx) =
+            self.preloading.iter().position(|p| *p == path)
+        {
+            self.preloading.remove(idx);
+            resource_loader.load_threaded_get(path.clone())
+        } else {
+            resource_loader.load(path.clone())
+        };
+        let Some(resource) = resource else {
+            godot_error!("Failed to load {}", path);
+            return None;
+        };
+        let Ok(scene) = resource.try_cast::<PackedScene>() else {
+            godot_error!("Failed to cast {} as a PackedScene", path);
+            return None;
+        };
+        Some(scene)
+    }
+
+    fn transition(&mut self, scene: Gd<PackedScene>, push_history: bool) {
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        let from = self.current_scene_path();
+        let to = scene.get_path();
+        self.base_mut().emit_signal(
+            "scene_changing".into(),
+            &[from.to_variant(), to.to_variant()],
+        );
+
+        let err = tree.change_scene_to_packed(scene);
+        if err != Error::OK {
+            godot_error!("Failed to change scene to {}: {:?}", to, err);
+            return;
+        }
+        if let Some(mut level_state) =
+            LevelState::find(self.base().clone().upcast())
+        {
+            level_state.bind_mut().leave(from == to);
+        }
+        if push_history && !from.is_empty() {
+            self.history.push(from);
+        }
+        self.pending = Some(to);
+    }
+}
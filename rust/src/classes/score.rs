@@ -0,0 +1,176 @@
+use godot::classes::{INode, Node};
+use godot::prelude::*;
+
+use super::level_state::LevelState;
+
+const GROUP: &str = "level_scoring";
+
+const BASE_SCORE: f64 = 1000.;
+const UNDER_PAR_BONUS: f64 = 10.;
+const OVER_PAR_PENALTY: f64 = 20.;
+const DEATH_PENALTY: f64 = 100.;
+const BUDGET_BONUS: f64 = 50.;
+const ACCURACY_BONUS: f64 = 500.;
+const HINT_PENALTY: f64 = 150.;
+
+const DEFAULT_PAR_TIME: f64 = 60.;
+const DEFAULT_STAR_SCORES: [i32; 3] = [1000, 1400, 1800];
+
+/// Everything a level's score is worked out from.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunStats {
+    pub time: f64,
+    pub deaths: u32,
+    /// What was left of the minigame's click budget.
+    pub budget_left: u32,
+    /// Total distance of the bars from the boss' targets, if the level has
+    /// a boss.
+    pub distance: Option<f32>,
+    pub hints: u32,
+}
+
+impl RunStats {
+    /// 1 when every bar is on target, falling towards 0 the further off
+    /// they are. Levels without a boss have nothing to aim for.
+    pub fn accuracy(&self) -> Option<f64> {
+        let distance = self.distance?;
+        Some(1. / (1. + distance.max(0.) as f64))
+    }
+
+    pub fn score(&self, par_time: f64) -> u32 {
+        let time = if self.time <= par_time {
+            (par_time - self.time) * UNDER_PAR_BONUS
+        } else {
+            (par_time - self.time) * OVER_PAR_PENALTY
+        };
+        let score = BASE_SCORE + time - self.deaths as f64 * DEATH_PENALTY
+            + self.budget_left as f64 * BUDGET_BONUS
+            + self.accuracy().unwrap_or(0.) * ACCURACY_BONUS
+            - self.hints as f64 * HINT_PENALTY;
+        score.max(0.) as u32
+    }
+}
+
+/// Number of thresholds in `star_scores` that `score` reaches.
+pub fn stars(score: u32, star_scores: &[i32]) -> u32 {
+    star_scores
+        .iter()
+        .filter(|threshold| score as i64 >= **threshold as i64)
+        .count() as u32
+}
+
+/// Per-level scoring setup. Levels without one are scored against the
+/// defaults.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct LevelScoring {
+    base: Base<Node>,
+    /// Seconds a good run of the level should take.
+    #[export]
+    par_time: f64,
+    /// Score needed for each star, lowest first.
+    #[export]
+    star_scores: PackedInt32Array,
+}
+
+#[godot_api]
+impl INode for LevelScoring {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            par_time: DEFAULT_PAR_TIME,
+            star_scores: PackedInt32Array::from(&DEFAULT_STAR_SCORES),
+        }
+    }
+
+    fn ready(&mut self) { self.base_mut().add_to_group(GROUP.into()); }
+}
+
+#[godot_api]
+impl LevelScoring {
+    /// Returns the scoring settings of the current level, if it has any.
+    pub fn find(node: Gd<Node>) -> Option<Gd<Self>> {
+        let mut tree = node.get_tree()?;
+        let scoring = tree.get_first_node_in_group(GROUP.into())?;
+        scoring.try_cast::<Self>().ok()
+    }
+
+    /// Scores the run so far in the level `node` is in, returning the stats
+    /// it was based on, the score and the stars earned.
+    pub fn rate(node: Gd<Node>) -> (RunStats, u32, u32) {
+        let stats = Self::collect_stats(node.clone());
+        let Some(scoring) = Self::find(node) else {
+            let score = stats.score(DEFAULT_PAR_TIME);
+            return (stats, score, stars(score, &DEFAULT_STAR_SCORES));
+        };
+        let scoring = scoring.bind();
+        let score = stats.score(scoring.par_time);
+        (stats, score, stars(score, scoring.star_scores.as_slice()))
+    }
+
+    /// Gathers the stats of the run so far in the level `node` is in.
+    fn collect_stats(node: Gd<Node>) -> RunStats {
+        let mut stats = RunStats::default();
+        if let Some(level_state) = LevelState::find(node.clone()) {
+            let level_state = level_state.bind();
+            stats.time = level_state.get_elapsed();
+            stats.deaths = level_state.get_deaths();
+            stats.hints = level_state.get_hints();
+        }
+        let Some(mut tree) = node.get_tree() else {
+            return stats;
+        };
+        if let Some(mut minigame) =
+            tree.get_first_node_in_group("minigame".into())
+        {
+            stats.budget_left = minigame.call("get_available".into(), &[]).to();
+        }
+        if let Some(mut boss) = tree.get_first_node_in_group("boss".into()) {
+            stats.distance = Some(boss.call("distance".into(), &[]).to());
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accuracy_bonus_needs_a_boss() {
+        let no_boss = RunStats {
+            time: 60.,
+            ..RunStats::default()
+        };
+        assert_eq!(no_boss.accuracy(), None);
+        assert_eq!(no_boss.score(60.), 1000);
+
+        let on_target = RunStats {
+            distance: Some(0.),
+            ..no_boss
+        };
+        assert_eq!(on_target.accuracy(), Some(1.));
+        assert_eq!(on_target.score(60.), 1500);
+
+        let off_target = RunStats {
+            distance: Some(1.),
+            ..no_boss
+        };
+        assert_eq!(off_target.accuracy(), Some(0.5));
+        assert_eq!(off_target.score(60.), 1250);
+    }
+
+    #[test]
+    fn hints_cost_points() {
+        let stats = RunStats {
+            time: 60.,
+            hints: 2,
+            ..RunStats::default()
+        };
+        assert_eq!(stats.score(60.), 700);
+
+        // The score bottoms out at zero rather than wrapping.
+        let stats = RunStats { hints: 10, ..stats };
+        assert_eq!(stats.score(60.), 0);
+    }
+}
@@ -0,0 +1,457 @@
+use godot::classes::display_server::WindowMode;
+use godot::classes::{
+    AudioServer, Button, CheckBox, Control, DisplayServer, HBoxContainer,
+    HSlider, IControl, INode, IResource, InputEvent, InputMap, Label, Node,
+    OptionButton, Resource, ResourceLoader, ResourceSaver, TranslationServer,
+    VBoxContainer,
+};
+use godot::global::{linear_to_db, Error};
+use godot::prelude::*;
+
+use super::locale::LANGUAGES;
+use super::scene_manager::{SceneManager, MAIN_MENU};
+
+pub const SETTINGS_PATH: &str = "user://settings.tres";
+pub const SETTINGS_MENU: &str = "res://scenes/settings.tscn";
+
+/// Actions the player is allowed to rebind from the settings menu.
+pub const REBINDABLE_ACTIONS: [&str; 13] = [
+    "MoveLeft",
+    "MoveRight",
+    "Jump",
+    "Interact",
+    "Reset",
+    "Pause",
+    "BarPrevious",
+    "BarNext",
+    "BarIncrease",
+    "BarDecrease",
+    "Hint",
+    "ZoomIn",
+    "ZoomOut",
+];
+
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct Settings {
+    #[export]
+    master_volume: f32,
+    #[export]
+    music_volume: f32,
+    #[export]
+    sfx_volume: f32,
+    #[export]
+    fullscreen: bool,
+    /// Locale of the game's text, or empty to follow the system language.
+    #[export]
+    locale: GString,
+    /// Action name to an array of `InputEvent`s, replacing the defaults from
+    /// `project.godot` for that action.
+    #[export]
+    bindings: Dictionary,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for Settings {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 1.,
+            sfx_volume: 1.,
+            fullscreen: false,
+            locale: GString::new(),
+            bindings: Dictionary::new(),
+            base,
+        }
+    }
+}
+
+#[godot_api]
+impl Settings {
+    /// Loads the saved settings, falling back to defaults on first launch.
+    pub fn load_or_default() -> Gd<Self> {
+        let mut resource_loader = ResourceLoader::singleton();
+        if !resource_loader.exists(SETTINGS_PATH.into()) {
+            return Self::new_gd();
+        }
+        let Some(settings) = resource_loader.load(SETTINGS_PATH.into()) else {
+            godot_error!("Failed to load settings");
+            return Self::new_gd();
+        };
+        let Ok(settings) = settings.try_cast::<Self>() else {
+            godot_error!("Failed to cast settings as Settings");
+            return Self::new_gd();
+        };
+        settings
+    }
+
+    #[func]
+    pub fn save(&self) {
+        let err = ResourceSaver::singleton()
+            .save_ex(self.to_gd().upcast())
+            .path(SETTINGS_PATH.into())
+            .done();
+        if err != Error::OK {
+            godot_error!("Failed to save settings: {:?}", err);
+        }
+    }
+
+    #[func]
+    pub fn apply(&self) {
+        Self::set_bus_volume("Master", self.master_volume);
+        Self::set_bus_volume("Music", self.music_volume);
+        Self::set_bus_volume("SFX", self.sfx_volume);
+
+        let mode = if self.fullscreen {
+            WindowMode::FULLSCREEN
+        } else {
+            WindowMode::WINDOWED
+        };
+        DisplayServer::singleton().window_set_mode(mode);
+
+        if !self.locale.is_empty() {
+            TranslationServer::singleton().set_locale(self.locale.clone());
+        }
+
+        let mut input_map = InputMap::singleton();
+        for (action, events) in self.bindings.iter_shared() {
+            let action: StringName = action.to();
+            if !input_map.has_action(action.clone()) {
+                godot_warn!("Ignoring binding for unknown action {}", action);
+                continue;
+            }
+            let Ok(events) = events.try_to::<VariantArray>() else {
+                godot_error!("Bindings for {} were not an array", action);
+                continue;
+            };
+            input_map.action_erase_events(action.clone());
+            for event in events.iter_shared() {
+                let Ok(event) = event.try_to::<Gd<InputEvent>>() else {
+                    continue;
+                };
+                input_map.action_add_event(action.clone(), event);
+            }
+        }
+    }
+
+    /// Copies the current `InputMap` events for every rebindable action into
+    /// `bindings`, so they are written out on the next save.
+    #[func]
+    pub fn capture_bindings(&mut self) {
+        let input_map = InputMap::singleton();
+        for action in REBINDABLE_ACTIONS {
+            let mut events = VariantArray::new();
+            for event in
+                input_map.action_get_events(action.into()).iter_shared()
+            {
+                events.push(event.to_variant());
+            }
+            self.bindings.set(action, events);
+        }
+    }
+
+    fn set_bus_volume(bus: &str, volume: f32) {
+        let mut audio_server = AudioServer::singleton();
+        let idx = audio_server.get_bus_index(bus.into());
+        if idx < 0 {
+            godot_warn!("No audio bus named {}", bus);
+            return;
+        }
+        audio_server.set_bus_volume_db(idx, linear_to_db(volume as f64) as f32);
+    }
+}
+
+/// Autoload that applies the saved [`Settings`] as the game starts.
+///
+/// Registered in `project.godot` as `UserSettings`, ahead of the other
+/// autoloads.
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct UserSettings {
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for UserSettings {
+    fn init(base: Base<Node>) -> Self { Self { base } }
+
+    fn ready(&mut self) { Settings::load_or_default().bind().apply(); }
+}
+
+/// Menu for editing [`Settings`]. Used both as its own scene from the main
+/// menu and as an overlay on the pause menu when `overlay` is set.
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct SettingsMenu {
+    base: Base<Control>,
+    #[export]
+    overlay: bool,
+    settings: Option<Gd<Settings>>,
+    rebind_buttons: Vec<(StringName, Gd<Button>)>,
+    rebinding: Option<StringName>,
+}
+
+#[godot_api]
+impl IControl for SettingsMenu {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            overlay: false,
+            settings: None,
+            rebind_buttons: Vec::new(),
+            rebinding: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let settings = Settings::load_or_default();
+        let this = self.to_gd();
+        let mut rows = VBoxContainer::new_alloc();
+
+        {
+            let settings = settings.bind();
+            rows.add_child(Self::slider_row(
+                &this,
+                "SETTINGS_MASTER_VOLUME",
+                settings.master_volume,
+                "master_volume_changed",
+            ));
+            rows.add_child(Self::slider_row(
+                &this,
+                "SETTINGS_MUSIC_VOLUME",
+                settings.music_volume,
+                "music_volume_changed",
+            ));
+            rows.add_child(Self::slider_row(
+                &this,
+                "SETTINGS_SFX_VOLUME",
+                settings.sfx_volume,
+                "sfx_volume_changed",
+            ));
+
+            let mut fullscreen = CheckBox::new_alloc();
+            fullscreen.set_text("SETTINGS_FULLSCREEN".into());
+            fullscreen.set_pressed(settings.fullscreen);
+            fullscreen.connect(
+                "toggled".into(),
+                Callable::from_object_method(&this, "fullscreen_toggled"),
+            );
+            rows.add_child(fullscreen);
+
+            rows.add_child(Self::language_row(&this, &settings.locale));
+        }
+
+        for action in REBINDABLE_ACTIONS {
+            let action = StringName::from(action);
+            let mut row = HBoxContainer::new_alloc();
+            let mut label = Label::new_alloc();
+            label.set_text(format!("ACTION_{}", action).to_uppercase().into());
+            label.set_custom_minimum_size(Vector2::new(120., 0.));
+            row.add_child(label);
+
+            let mut button = Button::new_alloc();
+            button.set_text(Self::binding_text(&action));
+            button.connect(
+                "pressed".into(),
+                Callable::from_object_method(&this, "start_rebind")
+                    .bindv(varray![action.clone()]),
+            );
+            row.add_child(button.clone());
+            rows.add_child(row);
+            self.rebind_buttons.push((action, button));
+        }
+
+        let mut back = Button::new_alloc();
+        back.set_text("MENU_BACK".into());
+        back.connect(
+            "pressed".into(),
+            Callable::from_object_method(&this, "close"),
+        );
+        rows.add_child(back);
+
+        self.settings = Some(settings);
+        let mut center = self.base().get_node_as::<Control>("Center");
+        center.add_child(rows);
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        let Some(action) = self.rebinding.clone() else {
+            return;
+        };
+        if !event.is_pressed() || event.is_echo() {
+            return;
+        }
+        let is_key = event.is_class("InputEventKey".into());
+        let is_joypad = event.is_class("InputEventJoypadButton".into());
+        if !is_key && !is_joypad {
+            return;
+        }
+        self.base().get_viewport().unwrap().set_input_as_handled();
+
+        // Only replace events from the same kind of device, so rebinding a
+        // key leaves the controller binding alone and vice versa.
+        let class = event.get_class();
+        let mut input_map = InputMap::singleton();
+        for existing in
+            input_map.action_get_events(action.clone()).iter_shared()
+        {
+            if existing.get_class() == class {
+                input_map.action_erase_event(action.clone(), existing);
+            }
+        }
+        input_map.action_add_event(action.clone(), event);
+        self.rebinding = None;
+        self.refresh_binding(&action);
+
+        let Some(ref mut settings) = self.settings else {
+            return;
+        };
+        settings.bind_mut().capture_bindings();
+        settings.bind().save();
+    }
+}
+
+#[godot_api]
+impl SettingsMenu {
+    #[signal]
+    fn closed();
+
+    #[func]
+    fn master_volume_changed(&mut self, value: f64) {
+        self.update(|settings| settings.master_volume = value as f32);
+    }
+
+    #[func]
+    fn music_volume_changed(&mut self, value: f64) {
+        self.update(|settings| settings.music_volume = value as f32);
+    }
+
+    #[func]
+    fn sfx_volume_changed(&mut self, value: f64) {
+        self.update(|settings| settings.sfx_volume = value as f32);
+    }
+
+    #[func]
+    fn fullscreen_toggled(&mut self, pressed: bool) {
+        self.update(|settings| settings.fullscreen = pressed);
+    }
+
+    #[func]
+    fn language_selected(&mut self, index: i64) {
+        let Some((locale, _)) = LANGUAGES.get(index as usize) else {
+            return;
+        };
+        self.update(|settings| settings.locale = (*locale).into());
+    }
+
+    #[func]
+    fn start_rebind(&mut self, action: StringName) {
+        for (name, button) in self.rebind_buttons.iter_mut() {
+            if *name == action {
+                button.set_text("SETTINGS_PRESS_KEY".into());
+            }
+        }
+        self.rebinding = Some(action);
+    }
+
+    #[func]
+    fn close(&mut self) {
+        if let Some(ref settings) = self.settings {
+            settings.bind().save();
+        }
+        if self.overlay {
+            self.base_mut().emit_signal("closed".into(), &[]);
+            self.base_mut().queue_free();
+            return;
+        }
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        let mut scene_manager = scene_manager.bind_mut();
+        if !scene_manager.back() {
+            scene_manager.change_scene(MAIN_MENU.into());
+        }
+    }
+
+    fn update(&mut self, change: impl FnOnce(&mut Settings)) {
+        let Some(ref mut settings) = self.settings else {
+            return;
+        };
+        change(&mut settings.bind_mut());
+        settings.bind().apply();
+    }
+
+    fn refresh_binding(&mut self, action: &StringName) {
+        for (name, button) in self.rebind_buttons.iter_mut() {
+            if name == action {
+                button.set_text(Self::binding_text(action));
+            }
+        }
+    }
+
+    fn binding_text(action: &StringName) -> GString {
+        let events = InputMap::singleton().action_get_events(action.clone());
+        let names: Vec<String> = events
+            .iter_shared()
+            .map(|event| event.as_text().to_string())
+            .collect();
+        names.join(", ").into()
+    }
+
+    fn language_row(this: &Gd<Self>, locale: &GString) -> Gd<HBoxContainer> {
+        let mut row = HBoxContainer::new_alloc();
+        let mut label = Label::new_alloc();
+        label.set_text("SETTINGS_LANGUAGE".into());
+        label.set_custom_minimum_size(Vector2::new(120., 0.));
+        row.add_child(label);
+
+        let current = if locale.is_empty() {
+            TranslationServer::singleton().get_locale()
+        } else {
+            locale.clone()
+        };
+        let current = current.to_string();
+        let mut languages = OptionButton::new_alloc();
+        for (idx, (code, name)) in LANGUAGES.iter().enumerate() {
+            languages.add_item((*name).into());
+            if current.starts_with(code) {
+                languages.select(idx as i32);
+            }
+        }
+        languages.connect(
+            "item_selected".into(),
+            Callable::from_object_method(this, "language_selected"),
+        );
+        row.add_child(languages);
+        row
+    }
+
+    fn slider_row(
+        this: &Gd<Self>,
+        name: &str,
+        value: f32,
+        method: &str,
+    ) -> Gd<HBoxContainer> {
+        let mut row = HBoxContainer::new_alloc();
+        let mut label = Label::new_alloc();
+        label.set_text(name.into());
+        label.set_custom_minimum_size(Vector2::new(120., 0.));
+        row.add_child(label);
+
+        let mut slider = HSlider::new_alloc();
+        slider.set_min(0.);
+        slider.set_max(1.);
+        slider.set_step(0.05);
+        slider.set_value(value as f64);
+        slider.set_custom_minimum_size(Vector2::new(200., 0.));
+        slider.connect(
+            "value_changed".into(),
+            Callable::from_object_method(this, method),
+        );
+        row.add_child(slider);
+        row
+    }
+}
@@ -0,0 +1,78 @@
+/// Formats seconds the way LiveSplit stores times, `HH:MM:SS.fffffff`.
+pub fn format_time(seconds: f64) -> String {
+    let ticks = (seconds.max(0.) * 10_000_000.).round() as u64;
+    let fraction = ticks % 10_000_000;
+    let total = ticks / 10_000_000;
+    format!(
+        "{:02}:{:02}:{:02}.{:07}",
+        total / 3600,
+        total / 60 % 60,
+        total % 60,
+        fraction
+    )
+}
+
+/// Builds a LiveSplit `.lss` file with one segment per entry in `names`.
+/// `splits` holds the personal best's time at the end of each segment and
+/// `best_segments` the fastest time ever taken on each one; either may be
+/// shorter than `names` if no full run has been recorded.
+pub fn to_lss(
+    game: &str,
+    category: &str,
+    attempts: u32,
+    names: &[String],
+    splits: &[f64],
+    best_segments: &[f64],
+) -> String {
+    let mut lss = String::new();
+    lss.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    lss.push_str("<Run version=\"1.7.0\">\n");
+    lss.push_str("  <GameIcon />\n");
+    lss.push_str(&format!("  <GameName>{}</GameName>\n", escape(game)));
+    lss.push_str(&format!(
+        "  <CategoryName>{}</CategoryName>\n",
+        escape(category)
+    ));
+    lss.push_str("  <Offset>00:00:00</Offset>\n");
+    lss.push_str(&format!("  <AttemptCount>{}</AttemptCount>\n", attempts));
+    lss.push_str("  <AttemptHistory />\n");
+    lss.push_str("  <Segments>\n");
+    for (idx, name) in names.iter().enumerate() {
+        lss.push_str("    <Segment>\n");
+        lss.push_str(&format!("      <Name>{}</Name>\n", escape(name)));
+        lss.push_str("      <Icon />\n");
+        lss.push_str("      <SplitTimes>\n");
+        match splits.get(idx) {
+            Some(split) => lss.push_str(&format!(
+                "        <SplitTime name=\"Personal Best\">\
+                 <RealTime>{}</RealTime></SplitTime>\n",
+                format_time(*split)
+            )),
+            None => {
+                lss.push_str("        <SplitTime name=\"Personal Best\" />\n")
+            }
+        }
+        lss.push_str("      </SplitTimes>\n");
+        match best_segments.get(idx) {
+            Some(best) => lss.push_str(&format!(
+                "      <BestSegmentTime><RealTime>{}</RealTime>\
+                 </BestSegmentTime>\n",
+                format_time(*best)
+            )),
+            None => lss.push_str("      <BestSegmentTime />\n"),
+        }
+        lss.push_str("      <SegmentHistory />\n");
+        lss.push_str("    </Segment>\n");
+    }
+    lss.push_str("  </Segments>\n");
+    lss.push_str("  <AutoSplitterSettings />\n");
+    lss.push_str("</Run>\n");
+    lss
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
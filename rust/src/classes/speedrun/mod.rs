@@ -0,0 +1,260 @@
+use godot::classes::file_access::ModeFlags;
+use godot::classes::node::ProcessMode;
+use godot::classes::{CanvasLayer, FileAccess, ICanvasLayer, Label};
+use godot::prelude::*;
+
+use super::locale::tr;
+use super::save_data::SaveData;
+use super::scene_manager::{SceneManager, CREDITS, LEVELS_DIR};
+
+mod lss;
+
+pub const SPLITS_PATH: &str = "user://splits.lss";
+
+/// Levels a speedrun goes through, in order. The run ends on leaving the
+/// last one.
+const RUN_LEVELS: [&str; 6] = [
+    "res://levels/level1.tscn",
+    "res://levels/level2.tscn",
+    "res://levels/level3.tscn",
+    "res://levels/level4.tscn",
+    "res://levels/level5.tscn",
+    "res://levels/level6.tscn",
+];
+
+/// Autoload that times a run through every level, showing the time in the
+/// corner of the screen and keeping splits and personal bests in the save
+/// data.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer)]
+pub struct Speedrun {
+    base: Base<CanvasLayer>,
+    running: bool,
+    finished: bool,
+    elapsed: f64,
+    splits: Vec<f64>,
+    best_splits: Vec<f64>,
+    label: Option<Gd<Label>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for Speedrun {
+    fn init(base: Base<CanvasLayer>) -> Self {
+        Self {
+            base,
+            running: false,
+            finished: false,
+            elapsed: 0.,
+            splits: Vec::new(),
+            best_splits: Vec::new(),
+            label: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+        self.base_mut().set_layer(9);
+
+        let mut label = Label::new_alloc();
+        label.set_position(Vector2::new(16., 16.));
+        self.base_mut().add_child(label.clone());
+        self.label = Some(label);
+        self.base_mut().hide();
+
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        let this = self.to_gd();
+        scene_manager.connect(
+            "scene_changing".into(),
+            Callable::from_object_method(&this, "scene_changing"),
+        );
+        scene_manager.connect(
+            "scene_changed".into(),
+            Callable::from_object_method(&this, "scene_changed"),
+        );
+    }
+
+    fn process(&mut self, delta: f64) {
+        if !self.running {
+            return;
+        }
+        let paused =
+            self.base().get_tree().map_or(true, |tree| tree.is_paused());
+        if !paused {
+            self.elapsed += delta;
+        }
+        self.update_label();
+    }
+}
+
+#[godot_api]
+impl Speedrun {
+    /// Looks up the `Speedrun` autoload from any node in the tree.
+    pub fn find(node: Gd<Node>) -> Option<Gd<Self>> {
+        let Some(tree) = node.get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return None;
+        };
+        let root = tree.get_root()?;
+        let speedrun = root.try_get_node_as::<Self>("Speedrun");
+        if speedrun.is_none() {
+            godot_error!("Speedrun autoload is missing!");
+        }
+        speedrun
+    }
+
+    /// Whether a speedrun is in progress, for features that should stay out
+    /// of the way of one.
+    pub fn is_running(node: Gd<Node>) -> bool {
+        Self::find(node).is_some_and(|speedrun| speedrun.bind().running)
+    }
+
+    #[func]
+    pub fn start(&mut self) {
+        let mut save_data = SaveData::load_or_default();
+        save_data.bind_mut().start_speedrun();
+        save_data.bind().save();
+        self.best_splits = save_data.bind().speedrun_splits().to_vec();
+
+        self.running = true;
+        self.finished = false;
+        self.elapsed = 0.;
+        self.splits.clear();
+        self.base_mut().show();
+
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        // Hold the base guard so `scene_changing` can be delivered to us
+        // while we're still bound.
+        let _guard = self.base_mut();
+        scene_manager.bind_mut().change_scene(RUN_LEVELS[0].into());
+    }
+
+    #[func]
+    fn scene_changing(&mut self, from: GString, to: GString) {
+        if !self.running || from == to {
+            return;
+        }
+        let Some(current) = RUN_LEVELS.get(self.splits.len()) else {
+            return;
+        };
+        if from.to_string() != *current {
+            return;
+        }
+        let last = self.splits.len() + 1 == RUN_LEVELS.len();
+        let next = RUN_LEVELS.get(self.splits.len() + 1);
+        let to = to.to_string();
+        if next.is_some_and(|next| to == *next)
+            || (last && to.starts_with(LEVELS_DIR))
+        {
+            self.splits.push(self.elapsed);
+            if last {
+                self.finish();
+            }
+        } else {
+            // Left for a menu, so the run is over.
+            self.stop();
+        }
+    }
+
+    #[func]
+    fn scene_changed(&mut self, path: GString) {
+        if !self.finished {
+            // Leave the final time up on the credits.
+            if !self.running && path.to_string() != CREDITS {
+                self.base_mut().hide();
+            }
+            return;
+        }
+        self.finished = false;
+        let Some(mut scene_manager) =
+            SceneManager::find(self.base().clone().upcast())
+        else {
+            return;
+        };
+        scene_manager.bind_mut().change_scene(CREDITS.into());
+    }
+
+    fn finish(&mut self) {
+        self.running = false;
+        self.finished = true;
+        let mut save_data = SaveData::load_or_default();
+        let personal_best = save_data.bind_mut().record_speedrun(&self.splits);
+        save_data.bind().save();
+        Self::export_splits(&save_data.bind());
+        self.update_label();
+        if personal_best {
+            if let Some(ref mut label) = self.label {
+                let text =
+                    format!("{}\n{}", label.get_text(), tr("SPEEDRUN_NEW_PB"));
+                label.set_text(text.into());
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        self.running = false;
+        self.base_mut().hide();
+    }
+
+    /// Writes the personal best and best segments out for LiveSplit.
+    fn export_splits(save_data: &SaveData) {
+        let names: Vec<String> = RUN_LEVELS
+            .iter()
+            .map(|level| {
+                let name = level.rsplit('/').next().unwrap_or(level);
+                name.trim_end_matches(".tscn").to_string()
+            })
+            .collect();
+        let lss = lss::to_lss(
+            "GMTK2024",
+            "All Levels",
+            save_data.speedrun_attempts(),
+            &names,
+            save_data.speedrun_splits(),
+            save_data.speedrun_best_segments(),
+        );
+        let Some(mut file) =
+            FileAccess::open(SPLITS_PATH.into(), ModeFlags::WRITE)
+        else {
+            godot_error!("Failed to open {} for writing", SPLITS_PATH);
+            return;
+        };
+        file.store_string(lss.into());
+        file.close();
+    }
+
+    fn update_label(&mut self) {
+        let mut text = format_time(self.elapsed);
+        // Compare the last split against the personal best's.
+        let last = self.splits.len().checked_sub(1);
+        if let Some((split, best)) = last.and_then(|idx| {
+            Some((self.splits[idx], *self.best_splits.get(idx)?))
+        }) {
+            let delta = split - best;
+            let sign = if delta < 0. { "-" } else { "+" };
+            text = format!("{}\n{}{}", text, sign, format_time(delta.abs()));
+        }
+        if let Some(ref mut label) = self.label {
+            label.set_text(text.into());
+        }
+    }
+}
+
+/// `M:SS.cc`, or `H:MM:SS.cc` past an hour.
+fn format_time(seconds: f64) -> String {
+    let centis = (seconds * 100.) as u64;
+    let (hours, minutes) = (centis / 360_000, centis / 6000 % 60);
+    let (secs, centis) = (centis / 100 % 60, centis % 100);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:02}", hours, minutes, secs, centis)
+    } else {
+        format!("{}:{:02}.{:02}", minutes, secs, centis)
+    }
+}
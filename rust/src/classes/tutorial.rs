@@ -0,0 +1,263 @@
+use godot::classes::control::LayoutPreset;
+use godot::classes::{
+    Button, CanvasItem, CanvasLayer, ICanvasLayer, IResource, Label,
+    PanelContainer, Resource, Time, VBoxContainer,
+};
+use godot::prelude::*;
+
+use super::dialogue::DialogueCondition;
+use super::graphs::bar::BarGraph;
+use super::save_data::SaveData;
+use super::speedrun::Speedrun;
+
+/// What the player has to do to finish a [`TutorialStep`].
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum TutorialTrigger {
+    Moved,
+    Jumped,
+    ClickedController,
+    BarReached,
+}
+
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct TutorialStep {
+    #[export(multiline)]
+    text: GString,
+    #[export]
+    trigger: TutorialTrigger,
+    /// Bar value to reach for `BarReached` steps.
+    #[export]
+    condition: Option<Gd<DialogueCondition>>,
+    /// Node to pulse while the step is shown, relative to the tutorial.
+    #[export]
+    highlight: NodePath,
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl IResource for TutorialStep {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            text: GString::new(),
+            trigger: TutorialTrigger::Moved,
+            condition: None,
+            highlight: NodePath::default(),
+            base,
+        }
+    }
+}
+
+/// Walks the player through `steps` one at a time, moving on as each one is
+/// done. Once finished or skipped it's remembered in the save and never
+/// shown again.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer)]
+pub struct Tutorial {
+    base: Base<CanvasLayer>,
+    /// Key the tutorial is saved under. Defaults to the level's scene path.
+    #[export]
+    id: GString,
+    #[export]
+    steps: Array<Gd<TutorialStep>>,
+    #[export]
+    highlight_colour: Color,
+    step: usize,
+    text: Option<Gd<Label>>,
+    highlighted: Option<(Gd<CanvasItem>, Color)>,
+    bar_graph: Option<Gd<BarGraph>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for Tutorial {
+    fn init(base: Base<CanvasLayer>) -> Self {
+        Self {
+            base,
+            id: GString::new(),
+            steps: Array::new(),
+            highlight_colour: Color::from_rgb(1., 0.85, 0.),
+            step: 0,
+            text: None,
+            highlighted: None,
+            bar_graph: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        if self.id.is_empty() {
+            if let Some(scene) = self
+                .base()
+                .get_tree()
+                .and_then(|tree| tree.get_current_scene())
+            {
+                self.id = scene.get_scene_file_path();
+            }
+        }
+        let finished = SaveData::load_or_default()
+            .bind()
+            .tutorial_finished(self.id.clone());
+        if finished
+            || self.steps.is_empty()
+            || Speedrun::is_running(self.base().clone().upcast())
+        {
+            self.base_mut().queue_free();
+            return;
+        }
+
+        let mut panel = PanelContainer::new_alloc();
+        panel.set_anchors_preset(LayoutPreset::CENTER_TOP);
+        panel.set_position(Vector2::new(-160., 16.));
+        panel.set_custom_minimum_size(Vector2::new(320., 0.));
+        let mut rows = VBoxContainer::new_alloc();
+        let text = Label::new_alloc();
+        let mut skip = Button::new_alloc();
+        skip.set_text("TUTORIAL_SKIP".into());
+        skip.connect(
+            "pressed".into(),
+            Callable::from_object_method(&self.to_gd(), "skip"),
+        );
+        rows.add_child(text.clone());
+        rows.add_child(skip);
+        panel.add_child(rows);
+        self.base_mut().add_child(panel);
+        self.text = Some(text);
+
+        self.connect_triggers();
+        self.show_step();
+    }
+
+    fn process(&mut self, _delta: f64) {
+        let Some((ref mut node, original)) = self.highlighted else {
+            return;
+        };
+        let seconds = Time::singleton().get_ticks_msec() as f32 / 1000.;
+        let pulse = (seconds * 4.).sin() * 0.5 + 0.5;
+        node.set_modulate(original.lerp(self.highlight_colour, pulse as f64));
+    }
+}
+
+#[godot_api]
+impl Tutorial {
+    #[signal]
+    fn finished(skipped: bool);
+
+    #[func]
+    fn skip(&mut self) { self.finish(true); }
+
+    #[func]
+    fn player_state_changed(&mut self, _from: GString, to: GString) {
+        match to.to_string().as_str() {
+            "Run" => self.complete(TutorialTrigger::Moved),
+            "Jump" => self.complete(TutorialTrigger::Jumped),
+            _ => {}
+        }
+    }
+
+    #[func]
+    fn controller_clicked(&mut self, _bar_index: i32) {
+        self.complete(TutorialTrigger::ClickedController);
+    }
+
+    #[func]
+    fn bar_changed(&mut self, _index: u32, _value: f32) {
+        let Some(step) = self.steps.get(self.step) else {
+            return;
+        };
+        let step = step.bind();
+        if step.trigger != TutorialTrigger::BarReached {
+            return;
+        }
+        let reached = step.condition.as_ref().map_or(true, |condition| {
+            condition.bind().holds(self.bar_graph.as_ref())
+        });
+        if reached {
+            self.complete(TutorialTrigger::BarReached);
+        }
+    }
+
+    fn connect_triggers(&mut self) {
+        let this = self.to_gd();
+        let Some(mut tree) = self.base().get_tree() else {
+            godot_error!("Could not get scene tree!");
+            return;
+        };
+        if let Some(mut player) = tree.get_first_node_in_group("player".into())
+        {
+            player.connect(
+                "state_changed".into(),
+                Callable::from_object_method(&this, "player_state_changed"),
+            );
+        }
+        if let Some(mut minigame) =
+            tree.get_first_node_in_group("minigame".into())
+        {
+            minigame.connect(
+                "controller_clicked".into(),
+                Callable::from_object_method(&this, "controller_clicked"),
+            );
+        }
+        self.bar_graph = BarGraph::find(self.base().clone().upcast());
+        if let Some(ref mut bar_graph) = self.bar_graph {
+            bar_graph.connect(
+                "bar_changed".into(),
+                Callable::from_object_method(&this, "bar_changed"),
+            );
+        }
+    }
+
+    /// Moves on to the next step if the current one waits on `trigger`.
+    fn complete(&mut self, trigger: TutorialTrigger) {
+        let Some(step) = self.steps.get(self.step) else {
+            return;
+        };
+        if step.bind().trigger != trigger {
+            return;
+        }
+        self.clear_highlight();
+        self.step += 1;
+        if self.step >= self.steps.len() {
+            self.finish(false);
+            return;
+        }
+        self.show_step();
+    }
+
+    fn show_step(&mut self) {
+        let Some(step) = self.steps.get(self.step) else {
+            return;
+        };
+        let step = step.bind();
+        if let Some(ref mut text) = self.text {
+            text.set_text(step.text.clone());
+        }
+        if step.highlight.is_empty() {
+            return;
+        }
+        let Some(node) = self
+            .base()
+            .try_get_node_as::<CanvasItem>(step.highlight.clone())
+        else {
+            godot_error!("Tutorial could not find {}", step.highlight);
+            return;
+        };
+        let original = node.get_modulate();
+        self.highlighted = Some((node, original));
+    }
+
+    fn clear_highlight(&mut self) {
+        if let Some((mut node, original)) = self.highlighted.take() {
+            node.set_modulate(original);
+        }
+    }
+
+    fn finish(&mut self, skipped: bool) {
+        self.clear_highlight();
+        let mut save_data = SaveData::load_or_default();
+        save_data.bind_mut().finish_tutorial(self.id.clone());
+        save_data.bind().save();
+        self.base_mut()
+            .emit_signal("finished".into(), &[skipped.to_variant()]);
+        self.base_mut().queue_free();
+    }
+}
@@ -1,6 +1,7 @@
 use godot::prelude::*;
 
 mod classes;
+pub mod puzzle;
 
 const GRAVITY: f32 = 1000.0;
 
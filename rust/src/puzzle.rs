@@ -0,0 +1,404 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// One bar of a bar graph puzzle: where it starts, the limits of the
+/// `BarController` driving it, and the range of values that count as on
+/// target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PuzzleBar {
+    pub start: u32,
+    pub minimum: u32,
+    pub maximum: u32,
+    pub target_min: u32,
+    pub target_max: u32,
+}
+
+impl PuzzleBar {
+    /// The on-target values the controller can actually reach, if any.
+    pub fn reachable_targets(&self) -> Option<(u32, u32)> {
+        let low = self.minimum.max(self.target_min);
+        let high = self.maximum.min(self.target_max);
+        (low <= high).then_some((low, high))
+    }
+
+    /// Which way to click to snap a count outside the controller's limits
+    /// back onto the nearest one, which doesn't spend any budget. Clicking
+    /// the other way moves such a count one step at full price, so snapping
+    /// first is always cheaper.
+    pub fn snap(&self) -> Option<bool> {
+        if self.start < self.minimum {
+            Some(false)
+        } else if self.start > self.maximum {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Fewest clicks that spend budget to bring this bar on target, after
+    /// any [`snap`](Self::snap).
+    pub fn clicks(&self) -> Option<u32> {
+        let (low, high) = self.reachable_targets()?;
+        let start = self.start.clamp(self.minimum, self.maximum);
+        Some(if start < low {
+            low - start
+        } else if start > high {
+            start - high
+        } else {
+            0
+        })
+    }
+}
+
+/// The bars of a level and the `MiniGame` click budget, if limited.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BarPuzzle {
+    pub bars: Vec<PuzzleBar>,
+    pub budget: Option<u32>,
+}
+
+impl BarPuzzle {
+    /// Fewest clicks that spend budget to get every bar on target, ignoring
+    /// the budget. `None` if a bar can't get there or there are too many
+    /// clicks to count.
+    pub fn min_clicks(&self) -> Option<u32> {
+        self.bars
+            .iter()
+            .try_fold(0u32, |total, bar| total.checked_add(bar.clicks()?))
+    }
+
+    pub fn is_solvable(&self) -> bool { self.solve().is_ok() }
+
+    /// Works out the cheapest way to put every bar on target. Each bar is
+    /// independent, so moving each one to its nearest on-target value is
+    /// optimal.
+    pub fn solve(&self) -> Result<Solution, Unsolvable> {
+        let mut moves = Vec::new();
+        let mut clicks = 0u32;
+        for (bar, puzzle_bar) in self.bars.iter().enumerate() {
+            let Some((low, _)) = puzzle_bar.reachable_targets() else {
+                return Err(Unsolvable::OutOfReach { bar });
+            };
+            let Some(count) = puzzle_bar.clicks() else {
+                return Err(Unsolvable::OutOfReach { bar });
+            };
+            if let Some(up) = puzzle_bar.snap() {
+                moves.push(Move {
+                    bar,
+                    up,
+                    clicks: 1,
+                    snap: true,
+                });
+            }
+            if count == 0 {
+                continue;
+            }
+            let start = puzzle_bar
+                .start
+                .clamp(puzzle_bar.minimum, puzzle_bar.maximum);
+            moves.push(Move {
+                bar,
+                up: start < low,
+                clicks: count,
+                snap: false,
+            });
+            clicks =
+                clicks.checked_add(count).ok_or(Unsolvable::TooManyClicks)?;
+        }
+        if let Some(budget) = self.budget {
+            if clicks > budget {
+                return Err(Unsolvable::OverBudget {
+                    needed: clicks,
+                    budget,
+                });
+            }
+        }
+        Ok(Solution { clicks, moves })
+    }
+}
+
+/// Clicks to make on one bar's controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub bar: usize,
+    pub up: bool,
+    pub clicks: u32,
+    /// A single free click snapping the count back onto the controller's
+    /// limits.
+    pub snap: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Solution {
+    /// Clicks that spend budget, leaving out snaps.
+    pub clicks: u32,
+    /// The clicks to make on each bar that needs changing, in bar order. A
+    /// bar outside its limits is snapped back before it's moved.
+    pub moves: Vec<Move>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unsolvable {
+    /// The bar's controller limits don't overlap its target range.
+    OutOfReach {
+        bar: usize,
+    },
+    OverBudget {
+        needed: u32,
+        budget: u32,
+    },
+    /// The clicks needed add up to more than a `u32` can count, which no
+    /// budget covers.
+    TooManyClicks,
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unsolvable::OutOfReach { bar } => {
+                write!(
+                    f,
+                    "bar {} can't reach its target within its limits",
+                    bar
+                )
+            }
+            Unsolvable::OverBudget { needed, budget } => write!(
+                f,
+                "needs {} clicks but the budget is only {}",
+                needed, budget
+            ),
+            Unsolvable::TooManyClicks => {
+                write!(f, "needs more clicks than can be counted")
+            }
+        }
+    }
+}
+
+/// Parses `start,minimum,maximum,target` where `target` is either a single
+/// value or an inclusive range such as `4..6`.
+impl FromStr for PuzzleBar {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = text.split(',').map(str::trim).collect();
+        let [start, minimum, maximum, target] = parts[..] else {
+            return Err(format!(
+                "expected start,minimum,maximum,target but got {:?}",
+                text
+            ));
+        };
+        let number = |part: &str| {
+            part.parse::<u32>()
+                .map_err(|err| format!("{:?} is not a count: {}", part, err))
+        };
+        let (target_min, target_max) = match target.split_once("..") {
+            Some((low, high)) => (number(low)?, number(high)?),
+            None => (number(target)?, number(target)?),
+        };
+        Ok(Self {
+            start: number(start)?,
+            minimum: number(minimum)?,
+            maximum: number(maximum)?,
+            target_min,
+            target_max,
+        })
+    }
+}
+
+/// Whole values within `tolerance` of `target`, as `(min, max)`.
+pub fn target_range(target: f32, tolerance: f32) -> (u32, u32) {
+    let low = (target - tolerance).ceil().max(0.);
+    let high = (target + tolerance).floor().max(0.);
+    (low as u32, high as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(
+        start: u32,
+        minimum: u32,
+        maximum: u32,
+        target: (u32, u32),
+    ) -> PuzzleBar {
+        PuzzleBar {
+            start,
+            minimum,
+            maximum,
+            target_min: target.0,
+            target_max: target.1,
+        }
+    }
+
+    #[test]
+    fn target_range_covers_whole_values_within_tolerance() {
+        assert_eq!(target_range(4., 0.5), (4, 4));
+        assert_eq!(target_range(4., 1.), (3, 5));
+        assert_eq!(target_range(4.5, 0.5), (4, 5));
+        assert_eq!(target_range(0., 2.), (0, 2));
+    }
+
+    #[test]
+    fn bars_need_a_click_per_step_to_the_nearest_target() {
+        assert_eq!(bar(2, 1, 8, (5, 6)).clicks(), Some(3));
+        assert_eq!(bar(8, 1, 8, (5, 6)).clicks(), Some(2));
+        assert_eq!(bar(5, 1, 8, (5, 6)).clicks(), Some(0));
+    }
+
+    #[test]
+    fn targets_outside_the_limits_are_out_of_reach() {
+        let bar = bar(3, 1, 4, target_range(6., 0.5));
+        assert_eq!(bar.reachable_targets(), None);
+        assert_eq!(bar.clicks(), None);
+
+        let puzzle = BarPuzzle {
+            bars: vec![bar],
+            budget: None,
+        };
+        assert_eq!(puzzle.min_clicks(), None);
+        assert!(!puzzle.is_solvable());
+    }
+
+    #[test]
+    fn generated_budgets_are_enough() {
+        // Plans the way the level generator does: a budget of the fewest
+        // clicks plus some slack always leaves the puzzle solvable.
+        let bars = vec![
+            bar(1, 1, 10, target_range(7., 0.5)),
+            bar(6, 2, 9, target_range(3., 0.5)),
+            bar(4, 4, 5, target_range(5., 1.)),
+        ];
+        let mut puzzle = BarPuzzle { bars, budget: None };
+        let clicks = puzzle.min_clicks();
+        assert_eq!(clicks, Some(9));
+        for slack in 0..=3 {
+            puzzle.budget = Some(9 + slack);
+            assert!(puzzle.is_solvable());
+        }
+        puzzle.budget = Some(8);
+        assert!(!puzzle.is_solvable());
+    }
+
+    #[test]
+    fn clicks_too_many_to_count_are_unsolvable() {
+        let far = bar(0, 0, u32::MAX, (u32::MAX, u32::MAX));
+        let single = BarPuzzle {
+            bars: vec![far],
+            budget: Some(u32::MAX),
+        };
+        assert_eq!(single.min_clicks(), Some(u32::MAX));
+        assert_eq!(single.solve().unwrap().clicks, u32::MAX);
+
+        let puzzle = BarPuzzle {
+            bars: vec![far, bar(1, 0, 2, (2, 2))],
+            budget: None,
+        };
+        assert_eq!(puzzle.min_clicks(), None);
+        assert_eq!(puzzle.solve(), Err(Unsolvable::TooManyClicks));
+    }
+
+    #[test]
+    fn solve_moves_each_bar_to_its_nearest_target() {
+        let puzzle = BarPuzzle {
+            bars: vec![
+                bar(2, 1, 8, (5, 6)),
+                bar(4, 1, 8, (4, 4)),
+                bar(8, 1, 8, (5, 6)),
+            ],
+            budget: Some(5),
+        };
+        let solution = puzzle.solve().unwrap();
+        assert_eq!(solution.clicks, 5);
+        assert_eq!(
+            solution.moves,
+            [
+                Move {
+                    bar: 0,
+                    up: true,
+                    clicks: 3,
+                    snap: false,
+                },
+                Move {
+                    bar: 2,
+                    up: false,
+                    clicks: 2,
+                    snap: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn solve_explains_why_it_gives_up() {
+        let mut puzzle = BarPuzzle {
+            bars: vec![bar(2, 1, 8, (5, 6)), bar(2, 1, 3, (5, 6))],
+            budget: None,
+        };
+        assert_eq!(puzzle.solve(), Err(Unsolvable::OutOfReach { bar: 1 }));
+
+        puzzle.bars.pop();
+        puzzle.budget = Some(2);
+        assert_eq!(
+            puzzle.solve(),
+            Err(Unsolvable::OverBudget {
+                needed: 3,
+                budget: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn counts_outside_the_limits_snap_back_first() {
+        // Like `BarController::adjust`, clicking down below the minimum
+        // snaps to it for free, where clicking up would cost a click a step.
+        let below = bar(0, 2, 8, (5, 5));
+        assert_eq!(below.snap(), Some(false));
+        assert_eq!(below.clicks(), Some(3));
+
+        let above = bar(12, 2, 8, (8, 9));
+        assert_eq!(above.snap(), Some(true));
+        assert_eq!(above.clicks(), Some(0));
+
+        let puzzle = BarPuzzle {
+            bars: vec![below, above],
+            budget: Some(3),
+        };
+        let solution = puzzle.solve().unwrap();
+        assert_eq!(solution.clicks, 3);
+        assert_eq!(
+            solution.moves,
+            [
+                Move {
+                    bar: 0,
+                    up: false,
+                    clicks: 1,
+                    snap: true,
+                },
+                Move {
+                    bar: 0,
+                    up: true,
+                    clicks: 3,
+                    snap: false,
+                },
+                Move {
+                    bar: 1,
+                    up: true,
+                    clicks: 1,
+                    snap: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bars_parse_from_the_command_line_format() {
+        assert_eq!("2,1,8,4".parse(), Ok(bar(2, 1, 8, (4, 4))));
+        assert_eq!(" 6, 2, 10, 3..5 ".parse(), Ok(bar(6, 2, 10, (3, 5))));
+        assert!("2,1,8".parse::<PuzzleBar>().is_err());
+        assert!("2,1,8,4,5".parse::<PuzzleBar>().is_err());
+        assert!("2,1,eight,4".parse::<PuzzleBar>().is_err());
+        assert!("2,1,8,-4".parse::<PuzzleBar>().is_err());
+        assert!("2,1,8,4..".parse::<PuzzleBar>().is_err());
+    }
+}